@@ -14,8 +14,11 @@ pub(crate) fn decrypt_from_reader<R: io::Read>(mut reader: R) -> anyhow::Result<
     let mut data = Vec::new();
     reader.read_to_end(&mut data)?;
 
-    dec.decrypt_padded_mut::<Pkcs7>(&mut data)
-        .map_err(|err| anyhow::anyhow!(err))?;
+    let len = dec
+        .decrypt_padded_mut::<Pkcs7>(&mut data)
+        .map_err(|err| anyhow::anyhow!(err))?
+        .len();
+    data.truncate(len);
 
     Ok(data)
 }
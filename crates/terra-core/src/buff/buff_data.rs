@@ -2,6 +2,8 @@ use std::io::{Read, Write};
 
 use byteorder::{ReadBytesExt, WriteBytesExt, LE};
 
+use crate::parse::FieldContext;
+
 #[derive(Default, Debug, Clone)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
@@ -12,8 +14,8 @@ pub struct Buff {
 
 impl Buff {
     pub fn load(&mut self, reader: &mut dyn Read) -> anyhow::Result<()> {
-        self.id = reader.read_i32::<LE>()?;
-        self.time = reader.read_i32::<LE>()?;
+        self.id = reader.read_i32::<LE>().field("id")?;
+        self.time = reader.read_i32::<LE>().field("time")?;
 
         Ok(())
     }
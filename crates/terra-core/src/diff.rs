@@ -0,0 +1,402 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+use crate::{Buff, Color, ContainerKind, Difficulty, Item, Player, ResearchItem, Spawnpoint, Team};
+
+/// One side of a `Change`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum ChangeValue {
+    /// The entry doesn't exist on this side, like a spawnpoint that was added.
+    Missing,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f32),
+    Text(String),
+    Color(Color),
+    Difficulty(Difficulty),
+    Team(Team),
+    Item {
+        id: i32,
+        stack: i32,
+        prefix: u8,
+        favourited: bool,
+    },
+    Buff {
+        id: i32,
+        time: i32,
+    },
+    Spawnpoint {
+        x: i32,
+        y: i32,
+        name: String,
+    },
+    Research {
+        stack: i32,
+    },
+}
+
+impl Display for ChangeValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing => write!(f, "nothing"),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::UInt(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value}"),
+            Self::Text(value) => write!(f, "{value:?}"),
+            Self::Color([r, g, b]) => write!(f, "#{r:02x}{g:02x}{b:02x}"),
+            Self::Difficulty(difficulty) => write!(f, "{difficulty}"),
+            Self::Team(team) => write!(f, "{team:?}"),
+            Self::Item { id: 0, .. } => write!(f, "empty"),
+            Self::Item {
+                id,
+                stack,
+                prefix,
+                favourited,
+            } => {
+                write!(f, "item {id} x{stack}")?;
+                if *prefix != 0 {
+                    write!(f, " with prefix {prefix}")?;
+                }
+                if *favourited {
+                    write!(f, " (favourited)")?;
+                }
+                Ok(())
+            }
+            Self::Buff { id: 0, .. } => write!(f, "empty"),
+            Self::Buff { id, time } => write!(f, "buff {id} for {time} ticks"),
+            Self::Spawnpoint { x, y, name } => write!(f, "({x}, {y}) in {name:?}"),
+            Self::Research { stack } => write!(f, "{stack} researched"),
+        }
+    }
+}
+
+macro_rules! impl_from_for_change_value {
+    ($variant:ident, $convert:ty, $($t:ty),+) => {
+        $(
+            impl From<$t> for ChangeValue {
+                fn from(value: $t) -> Self {
+                    Self::$variant(<$convert>::from(value))
+                }
+            }
+        )+
+    };
+}
+
+impl_from_for_change_value!(Bool, bool, bool);
+impl_from_for_change_value!(Int, i64, u8, i32, u32, i64);
+impl_from_for_change_value!(UInt, u64, u64);
+impl_from_for_change_value!(Float, f32, f32);
+impl_from_for_change_value!(Text, String, String);
+impl_from_for_change_value!(Color, Color, Color);
+impl_from_for_change_value!(Difficulty, Difficulty, Difficulty);
+impl_from_for_change_value!(Team, Team, Team);
+
+impl From<Item> for ChangeValue {
+    fn from(item: Item) -> Self {
+        Self::Item {
+            id: item.id,
+            stack: item.stack,
+            prefix: item.prefix.id,
+            favourited: item.favourited,
+        }
+    }
+}
+
+impl From<Buff> for ChangeValue {
+    fn from(buff: Buff) -> Self {
+        Self::Buff {
+            id: buff.id,
+            time: buff.time,
+        }
+    }
+}
+
+impl From<Spawnpoint> for ChangeValue {
+    fn from(spawnpoint: Spawnpoint) -> Self {
+        Self::Spawnpoint {
+            x: spawnpoint.x,
+            y: spawnpoint.y,
+            name: spawnpoint.name,
+        }
+    }
+}
+
+impl From<ResearchItem> for ChangeValue {
+    fn from(research: ResearchItem) -> Self {
+        Self::Research {
+            stack: research.stack,
+        }
+    }
+}
+
+/// A value that differs between two players, found by `Player::diff`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct Change {
+    /// Where the value is, like `max_life` or `loadouts[1].accessories[3]`.
+    pub path: String,
+    pub old: ChangeValue,
+    pub new: ChangeValue,
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} -> {}", self.path, self.old, self.new)
+    }
+}
+
+/// Whether two values differ, as far as the diff is concerned.
+trait Compare {
+    fn same(&self, other: &Self) -> bool;
+}
+
+macro_rules! impl_compare_with_eq {
+    ($($t:ty),+) => {
+        $(
+            impl Compare for $t {
+                fn same(&self, other: &Self) -> bool {
+                    self == other
+                }
+            }
+        )+
+    };
+}
+
+impl_compare_with_eq!(bool, u8, i32, u32, i64, u64, f32, String, Color, Difficulty, Team);
+
+impl Compare for Item {
+    /// Empty slots are all the same, whatever stack they were saved with. tModLoader data
+    /// isn't compared, only what's in the `.plr`.
+    fn same(&self, other: &Self) -> bool {
+        if self.id == 0 && other.id == 0 {
+            return true;
+        }
+
+        self.id == other.id
+            && self.stack == other.stack
+            && self.prefix.id == other.prefix.id
+            && self.favourited == other.favourited
+    }
+}
+
+impl Compare for Buff {
+    fn same(&self, other: &Self) -> bool {
+        self.id == other.id && self.time == other.time
+    }
+}
+
+impl Compare for Spawnpoint {
+    fn same(&self, other: &Self) -> bool {
+        self.x == other.x && self.y == other.y && self.name == other.name
+    }
+}
+
+impl Compare for ResearchItem {
+    fn same(&self, other: &Self) -> bool {
+        self.stack == other.stack
+    }
+}
+
+#[derive(Default)]
+struct Differ {
+    changes: Vec<Change>,
+}
+
+impl Differ {
+    fn field<T>(&mut self, path: impl FnOnce() -> String, old: &T, new: &T)
+    where
+        T: Compare + Clone + Into<ChangeValue>,
+    {
+        if !old.same(new) {
+            self.changes.push(Change {
+                path: path(),
+                old: old.clone().into(),
+                new: new.clone().into(),
+            });
+        }
+    }
+
+    /// Compares two lists by index, where one may be longer than the other.
+    fn list<T>(&mut self, name: &str, old: &[T], new: &[T])
+    where
+        T: Compare + Clone + Into<ChangeValue>,
+    {
+        for i in 0..old.len().max(new.len()) {
+            self.keyed(|| format!("{name}[{i}]"), old.get(i), new.get(i));
+        }
+    }
+
+    /// Compares two entries which may be missing on either side.
+    fn keyed<T>(&mut self, path: impl FnOnce() -> String, old: Option<&T>, new: Option<&T>)
+    where
+        T: Compare + Clone + Into<ChangeValue>,
+    {
+        let value = |value: Option<&T>| value.map_or(ChangeValue::Missing, |v| v.clone().into());
+
+        match (old, new) {
+            (Some(old), Some(new)) => self.field(path, old, new),
+            (None, None) => {}
+            (old, new) => self.changes.push(Change {
+                path: path(),
+                old: value(old),
+                new: value(new),
+            }),
+        }
+    }
+}
+
+/// Compares plain fields, using the field's name as the path.
+macro_rules! diff_fields {
+    ($differ:expr, $old:expr, $new:expr, $($field:ident),+ $(,)?) => {
+        $(
+            $differ.field(|| stringify!($field).to_owned(), &$old.$field, &$new.$field);
+        )+
+    };
+}
+
+/// Compares lists and arrays by index, using the field's name as the path.
+macro_rules! diff_lists {
+    ($differ:expr, $old:expr, $new:expr, $($field:ident),+ $(,)?) => {
+        $(
+            $differ.list(stringify!($field), &$old.$field, &$new.$field);
+        )+
+    };
+}
+
+impl Player {
+    /// Lists every value that differs between this player and `other`, with this player's
+    /// value as the old one.
+    ///
+    /// Research is keyed by the item's internal name and spawnpoints by their world's id, so
+    /// reordering them isn't a change. Journey powers and tModLoader data aren't compared.
+    pub fn diff(&self, other: &Player) -> Vec<Change> {
+        let mut differ = Differ::default();
+
+        diff_fields!(
+            differ,
+            self,
+            other,
+            is_mobile,
+            version,
+            revision,
+            favourited,
+            name,
+            difficulty,
+            playtime,
+            hair_style,
+            hair_dye,
+            male,
+            skin_variant,
+            life,
+            max_life,
+            mana,
+            max_mana,
+            demon_heart,
+            biome_torches,
+            biome_torches_enabled,
+            artisan_loaf,
+            vital_crystal,
+            aegis_fruit,
+            arcane_crystal,
+            galaxy_pearl,
+            gummy_worm,
+            ambrosia,
+            defeated_ooa,
+            tax_money,
+            pve_deaths,
+            pvp_deaths,
+            hair_color,
+            skin_color,
+            eye_color,
+            shirt_color,
+            undershirt_color,
+            pants_color,
+            shoe_color,
+            void_vault_enabled,
+            locked_hotbar,
+            angler_quests,
+            tavernkeep_quests,
+            dead,
+            respawn_timer,
+            last_save,
+            golfer_score,
+            share_research,
+            super_cart,
+            super_cart_enabled,
+            current_loadout_index,
+            team,
+            voice_variant,
+            voice_pitch_offset,
+        );
+
+        diff_lists!(
+            differ,
+            self,
+            other,
+            hide_equipment,
+            buffs,
+            hide_cellphone_info,
+            dpad_bindings,
+            builder_accessory_status,
+            one_time_dialogues_seen,
+        );
+
+        for (i, (old, new)) in self.loadouts.iter().zip(&other.loadouts).enumerate() {
+            differ.list(
+                &format!("loadouts[{i}].hide_visual"),
+                &old.hide_visual,
+                &new.hide_visual,
+            );
+        }
+        for container in ContainerKind::iter() {
+            if let (Some(old), Some(new)) = (container.items(self), container.items(other)) {
+                differ.list(&container.to_string(), old, new);
+            }
+        }
+
+        let (old_research, new_research) = (research_by_name(self), research_by_name(other));
+        let names: BTreeSet<_> = old_research.keys().chain(new_research.keys()).collect();
+        for name in names {
+            differ.keyed(
+                || format!("research[{name:?}]"),
+                old_research.get(name).copied(),
+                new_research.get(name).copied(),
+            );
+        }
+
+        let (old_spawnpoints, new_spawnpoints) =
+            (spawnpoints_by_world(self), spawnpoints_by_world(other));
+        let ids: BTreeSet<_> = old_spawnpoints
+            .keys()
+            .chain(new_spawnpoints.keys())
+            .collect();
+        for id in ids {
+            differ.keyed(
+                || format!("spawnpoints[{id}]"),
+                old_spawnpoints.get(id).copied(),
+                new_spawnpoints.get(id).copied(),
+            );
+        }
+
+        differ.changes
+    }
+}
+
+fn research_by_name(player: &Player) -> BTreeMap<String, &ResearchItem> {
+    player
+        .research
+        .iter()
+        .map(|r| (r.internal_name.to_string(), r))
+        .collect()
+}
+
+fn spawnpoints_by_world(player: &Player) -> BTreeMap<i32, &Spawnpoint> {
+    player.spawnpoints.iter().map(|s| (s.id, s)).collect()
+}
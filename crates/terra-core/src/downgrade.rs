@@ -0,0 +1,438 @@
+use std::fmt::Display;
+
+use crate::{
+    ContainerKind, Difficulty, Item, Loadout, Player, PlayerError, SlotRef, LOADOUT_COUNT,
+};
+
+/// The number of items in each release, keyed by the first player version of that release.
+///
+/// Releases that aren't listed use the count of the last one before them, which may remove
+/// items that would have been fine. Versions from 1.4.5 onwards aren't limited.
+const ITEM_COUNTS: [(i32, Option<i32>); 11] = [
+    // 1.0.6.1 (22), whose count older 1.0 versions are also given
+    (0, Some(364)),
+    // 1.1
+    (36, Some(586)),
+    // 1.1.2
+    (39, Some(604)),
+    // 1.2
+    (67, Some(1725)),
+    // 1.2.4.1
+    (102, Some(2749)),
+    // 1.3.0.1
+    (146, Some(3602)),
+    // 1.3.5
+    (191, Some(3930)),
+    // 1.4.0.1
+    (225, Some(5044)),
+    // 1.4.3
+    (242, Some(5125)),
+    // 1.4.4
+    (269, Some(5453)),
+    // 1.4.5
+    (315, None),
+];
+
+/// The highest item id + 1 that a version of the game knows about, or `None` if all
+/// current items are known.
+pub fn item_count(version: i32) -> Option<i32> {
+    ITEM_COUNTS
+        .iter()
+        .rev()
+        .find(|(since, _)| version >= *since)
+        .and_then(|(_, count)| *count)
+}
+
+/// Something `Player::downgrade_to` removed or changed.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum DowngradeChange {
+    /// The item's id doesn't exist in the target version.
+    UnknownItem { slot: String, id: i32 },
+    /// The target version doesn't have the slot the item was in.
+    MissingSlot { slot: String, id: i32 },
+    /// Prefixes aren't saved before version 36.
+    DroppedPrefix { slot: String, prefix: u8 },
+    /// The field isn't saved before version `since`, so it was reset.
+    DroppedField { field: String, since: i32 },
+    /// Journey mode doesn't exist before 1.4.
+    Difficulty { from: Difficulty, to: Difficulty },
+    /// Data terra-rs couldn't read, which is laid out for the original version.
+    DroppedRaw { field: String },
+}
+
+impl Display for DowngradeChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownItem { slot, id } => {
+                write!(f, "Removed item {id} from {slot}, as it doesn't exist yet")
+            }
+            Self::MissingSlot { slot, id } => {
+                write!(
+                    f,
+                    "Removed item {id} from {slot}, as the slot doesn't exist yet"
+                )
+            }
+            Self::DroppedPrefix { slot, prefix } => {
+                write!(f, "Removed prefix {prefix} from the item in {slot}")
+            }
+            Self::DroppedField { field, since } => {
+                write!(
+                    f,
+                    "Reset {field}, which is only saved since version {since}"
+                )
+            }
+            Self::Difficulty { from, to } => write!(f, "Changed difficulty from {from} to {to}"),
+            Self::DroppedRaw { field } => {
+                write!(f, "Removed the data terra-rs couldn't read from {field}")
+            }
+        }
+    }
+}
+
+/// Everything that was lost when downgrading a player.
+#[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct DowngradeReport {
+    pub from: i32,
+    pub to: i32,
+    pub changes: Vec<DowngradeChange>,
+}
+
+impl DowngradeReport {
+    pub fn is_lossless(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// What a loadout would lose as loadout `index` of a version `version` player, where the
+    /// loadout exists.
+    pub(crate) fn for_loadout(loadout: &Loadout, index: usize, version: i32) -> Self {
+        let mut report = Self {
+            from: version,
+            to: version,
+            changes: Vec::new(),
+        };
+        let mut player = Player::default();
+        player.loadouts[index] = loadout.clone();
+        report.items(&mut player, |_| true);
+        report
+    }
+
+    fn item(&mut self, slot: impl FnOnce() -> String, item: &mut Item, slot_exists: bool) {
+        if item.id == 0 {
+            return;
+        }
+
+        if !slot_exists {
+            self.changes.push(DowngradeChange::MissingSlot {
+                slot: slot(),
+                id: item.id,
+            });
+            *item = Item::default();
+        } else if item_count(self.to).is_some_and(|count| item.id >= count) {
+            self.changes.push(DowngradeChange::UnknownItem {
+                slot: slot(),
+                id: item.id,
+            });
+            *item = Item::default();
+        } else if self.to < 36 && item.prefix.id != 0 {
+            self.changes.push(DowngradeChange::DroppedPrefix {
+                slot: slot(),
+                prefix: item.prefix.id,
+            });
+            item.prefix.id = 0;
+        }
+    }
+
+    /// Whether a version `self.to` player has the slot, ignoring whether its loadout exists.
+    fn slot_exists(&self, slot: SlotRef) -> bool {
+        let to = self.to;
+        let inventory_count = if to >= 58 { 50 } else { 40 };
+        let bank_count = if to >= 58 { 40 } else { 20 };
+        let accessory_count = if to >= 124 { 7 } else { 5 };
+        let i = slot.index;
+
+        match slot.container {
+            ContainerKind::Inventory => i < inventory_count,
+            ContainerKind::Coins | ContainerKind::Armor(_) => true,
+            ContainerKind::Ammo => to >= 15,
+            ContainerKind::PiggyBank => i < bank_count,
+            ContainerKind::Safe => to >= 20 && i < bank_count,
+            ContainerKind::DefendersForge => to >= 182 && i < bank_count,
+            ContainerKind::VoidVault => to >= 198 && i < bank_count,
+            ContainerKind::Equipment | ContainerKind::EquipmentDyes => {
+                to >= 117 && (to >= 136 || i >= 1)
+            }
+            ContainerKind::VanityArmor(_) => to >= 6,
+            ContainerKind::ArmorDyes(_) => to >= 47,
+            ContainerKind::Accessories(_) => i < accessory_count,
+            ContainerKind::VanityAccessories(_) | ContainerKind::AccessoryDyes(_) => {
+                to >= 81 && i < accessory_count
+            }
+            ContainerKind::TemporarySlots => to >= 214,
+            ContainerKind::PendingRefunds => to >= 300,
+        }
+    }
+
+    /// Removes the items in every slot the player loses, and anything else the version can't
+    /// hold. `loadout_exists` says which loadouts are saved at all.
+    fn items(&mut self, player: &mut Player, loadout_exists: impl Fn(usize) -> bool) {
+        for container in ContainerKind::iter() {
+            let loadout_exists = container.loadout().is_none_or(&loadout_exists);
+            let Some(items) = container.items_mut(player) else {
+                continue;
+            };
+
+            for (i, item) in items.iter_mut().enumerate() {
+                let slot = SlotRef::new(container, i);
+                let exists = loadout_exists && self.slot_exists(slot);
+                self.item(|| slot.to_string(), item, exists);
+            }
+        }
+    }
+
+    fn field<T: PartialEq>(&mut self, since: i32, field: &str, value: &mut T, default: T) {
+        if self.to < since && *value != default {
+            self.changes.push(DowngradeChange::DroppedField {
+                field: field.to_owned(),
+                since,
+            });
+            *value = default;
+        }
+    }
+}
+
+impl Player {
+    /// Converts the player to an older file version, so it can be saved for that version
+    /// of the game.
+    ///
+    /// Anything the older version can't hold is removed or reset, and listed in the report.
+    /// Use `downgrade_report` to see what would change without modifying the player.
+    pub fn downgrade_to(&mut self, version: i32) -> anyhow::Result<DowngradeReport> {
+        if version < 1 || version > self.version {
+            return Err(PlayerError::InvalidDowngrade {
+                from: self.version,
+                to: version,
+            }
+            .into());
+        }
+
+        let mut report = DowngradeReport {
+            from: self.version,
+            to: version,
+            changes: Vec::new(),
+        };
+        let default = Player::default();
+
+        if self.difficulty == Difficulty::Journey && version < 225 {
+            report.changes.push(DowngradeChange::Difficulty {
+                from: self.difficulty,
+                to: Difficulty::Classic,
+            });
+            self.difficulty = Difficulty::Classic;
+        }
+        if version < 17 && self.difficulty == Difficulty::Mediumcore {
+            report.changes.push(DowngradeChange::Difficulty {
+                from: self.difficulty,
+                to: Difficulty::Classic,
+            });
+            self.difficulty = Difficulty::Classic;
+        }
+        report.field(10, "difficulty", &mut self.difficulty, default.difficulty);
+
+        report.field(138, "playtime", &mut self.playtime, default.playtime);
+        report.field(82, "hair_dye", &mut self.hair_dye, default.hair_dye);
+        report.field(283, "team", &mut self.team, default.team);
+        report.field(
+            119,
+            "hide_equipment",
+            &mut self.hide_equipment,
+            default.hide_equipment,
+        );
+
+        // Permanent bonuses
+        report.field(125, "demon_heart", &mut self.demon_heart, false);
+        report.field(229, "biome_torches", &mut self.biome_torches, false);
+        report.field(
+            229,
+            "biome_torches_enabled",
+            &mut self.biome_torches_enabled,
+            false,
+        );
+        report.field(256, "artisan_loaf", &mut self.artisan_loaf, false);
+        report.field(260, "vital_crystal", &mut self.vital_crystal, false);
+        report.field(260, "aegis_fruit", &mut self.aegis_fruit, false);
+        report.field(260, "arcane_crystal", &mut self.arcane_crystal, false);
+        report.field(260, "galaxy_pearl", &mut self.galaxy_pearl, false);
+        report.field(260, "gummy_worm", &mut self.gummy_worm, false);
+        report.field(260, "ambrosia", &mut self.ambrosia, false);
+        report.field(182, "defeated_ooa", &mut self.defeated_ooa, false);
+
+        report.field(128, "tax_money", &mut self.tax_money, default.tax_money);
+        report.field(256, "pve_deaths", &mut self.pve_deaths, default.pve_deaths);
+        report.field(256, "pvp_deaths", &mut self.pvp_deaths, default.pvp_deaths);
+
+        // Loadouts, of which only the equipped one exists before 1.4.4
+        if version < 262 && self.current_loadout_index != 0 {
+            let current = self
+                .current_loadout_index
+                .clamp(0, LOADOUT_COUNT as i32 - 1);
+            self.loadouts.swap(0, current as usize);
+            self.current_loadout_index = 0;
+        }
+        let current = self.current_loadout_index.max(0) as usize;
+        let loadout_exists = |i| i == current || version >= 262;
+        report.items(self, loadout_exists);
+        for (i, loadout) in self.loadouts.iter_mut().enumerate() {
+            if !loadout_exists(i) {
+                loadout.hide_visual = Loadout::default().hide_visual;
+            }
+        }
+
+        report.field(
+            199,
+            "void_vault_enabled",
+            &mut self.void_vault_enabled,
+            default.void_vault_enabled,
+        );
+        self.pending_refunds.retain(|item| item.id != 0);
+
+        for (i, buff) in self.buffs.iter_mut().enumerate() {
+            let since = match i {
+                0..10 => 11,
+                10..22 => 74,
+                _ => 252,
+            };
+            if version < since && buff.id != 0 {
+                report.changes.push(DowngradeChange::DroppedField {
+                    field: format!("buffs[{i}]"),
+                    since,
+                });
+                *buff = Default::default();
+            }
+        }
+
+        report.field(
+            16,
+            "locked_hotbar",
+            &mut self.locked_hotbar,
+            default.locked_hotbar,
+        );
+        report.field(
+            115,
+            "hide_cellphone_info",
+            &mut self.hide_cellphone_info,
+            default.hide_cellphone_info,
+        );
+        report.field(
+            98,
+            "angler_quests",
+            &mut self.angler_quests,
+            default.angler_quests,
+        );
+        report.field(
+            162,
+            "dpad_bindings",
+            &mut self.dpad_bindings,
+            default.dpad_bindings,
+        );
+        for (i, status) in self.builder_accessory_status.iter_mut().enumerate() {
+            let since = match i {
+                0..8 => 164,
+                8..10 => 167,
+                10 => 197,
+                _ => 230,
+            };
+            let field = format!("builder_accessory_status[{i}]");
+            report.field(since, &field, status, default.builder_accessory_status[i]);
+        }
+        report.field(
+            181,
+            "tavernkeep_quests",
+            &mut self.tavernkeep_quests,
+            default.tavernkeep_quests,
+        );
+        report.field(200, "dead", &mut self.dead, default.dead);
+        report.field(
+            200,
+            "respawn_timer",
+            &mut self.respawn_timer,
+            default.respawn_timer,
+        );
+        report.field(
+            206,
+            "golfer_score",
+            &mut self.golfer_score,
+            default.golfer_score,
+        );
+
+        // Journey mode
+        report.field(
+            282,
+            "share_research",
+            &mut self.share_research,
+            default.share_research,
+        );
+        if version < 218 && !self.research.is_empty() {
+            report.changes.push(DowngradeChange::DroppedField {
+                field: "research".to_owned(),
+                since: 218,
+            });
+            self.research.clear();
+        }
+        report.field(
+            220,
+            "journey_powers",
+            &mut self.journey_powers,
+            default.journey_powers,
+        );
+        // An unrecognised power holds the rest of the player, laid out for its own version
+        if version != self.version && self.journey_powers.has_raw_tail() {
+            self.journey_powers.powers.pop();
+            report.changes.push(DowngradeChange::DroppedRaw {
+                field: "journey_powers".to_owned(),
+            });
+        }
+
+        report.field(253, "super_cart", &mut self.super_cart, default.super_cart);
+        report.field(
+            253,
+            "super_cart_enabled",
+            &mut self.super_cart_enabled,
+            default.super_cart_enabled,
+        );
+
+        // Voice
+        report.field(
+            280,
+            "voice_variant",
+            &mut self.voice_variant,
+            if self.male { 1 } else { 2 },
+        );
+        report.field(
+            281,
+            "voice_pitch_offset",
+            &mut self.voice_pitch_offset,
+            default.voice_pitch_offset,
+        );
+
+        if version < 310 && !self.one_time_dialogues_seen.is_empty() {
+            report.changes.push(DowngradeChange::DroppedField {
+                field: "one_time_dialogues_seen".to_owned(),
+                since: 310,
+            });
+            self.one_time_dialogues_seen.clear();
+        }
+
+        self.version = version;
+
+        Ok(report)
+    }
+
+    /// Lists what `downgrade_to` would remove or change, without modifying the player.
+    pub fn downgrade_report(&self, version: i32) -> anyhow::Result<DowngradeReport> {
+        self.clone().downgrade_to(version)
+    }
+}
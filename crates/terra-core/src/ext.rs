@@ -109,16 +109,18 @@ pub trait TerraWriteExt: io::Write {
     fn write_uleb128_usize(&mut self, value: usize) -> IOResult<()> {
         let mut copy = value;
 
+        // Zero still needs a byte, otherwise empty strings aren't written at all
         loop {
-            if copy == 0 {
-                break;
-            }
             let mut byte = (copy & 0x7f) as u8;
             copy >>= 7;
             if copy != 0 {
                 byte |= 0x80;
             }
             self.write_u8(byte)?;
+
+            if copy == 0 {
+                break;
+            }
         }
 
         Ok(())
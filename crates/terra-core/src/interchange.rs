@@ -0,0 +1,648 @@
+mod document;
+
+pub use document::{
+    AppearanceDocument, BuffDocument, DifficultyName, EquipmentDocument, EquipmentSlotDocument,
+    GearSlotDocument, HexColor, InterfaceDocument, ItemDocument, ItemSlot, JourneyPowerDocument,
+    JourneyPowerValueDocument, LoadoutDocument, MetaKey, PlayerDocument, ResearchDocument,
+    SpawnpointDocument, StatsDocument, TeamName, TemporarySlotsDocument, UpgradesDocument,
+};
+
+use crate::{
+    meta::{Meta, MetaRegistry},
+    Buff, Item, JourneyPower, JourneyPowerError, JourneyPowerId, JourneyPowerValue, JourneyPowers,
+    Loadout, MetaBundle, Player, Prefix, ResearchItem, Spawnpoint, ACCESSORY_COUNT, ARMOR_COUNT,
+    CURRENT_VERSION,
+};
+
+/// The version of `PlayerDocument` this terra-rs writes, and the newest it can read.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The JSON Schema of `PlayerDocument`, also at `data/schema/player.schema.json`.
+pub const PLAYER_SCHEMA: &str = include_str!("../../../data/schema/player.schema.json");
+
+#[derive(thiserror::Error, Debug)]
+pub enum InterchangeError {
+    #[error("The document has no format_version.")]
+    MissingFormatVersion,
+    #[error("The document's format version ({0}) is newer than terra-rs supports (<= {FORMAT_VERSION}).")]
+    UnsupportedFormat(u64),
+    #[error("The player is for a newer version of Terraria ({0}) than terra-rs supports (<= {CURRENT_VERSION}).")]
+    PostDated(i32),
+    #[error("Unknown {kind} `{name}` at {path}.")]
+    UnknownName {
+        kind: &'static str,
+        name: String,
+        path: String,
+    },
+    #[error("{path} has {found} slots, but players only have {count}.")]
+    TooManySlots {
+        path: String,
+        found: usize,
+        count: usize,
+    },
+    #[error("Invalid journey_powers: {source}")]
+    JourneyPowers { source: JourneyPowerError },
+}
+
+/// The snake_case name of a journey power, as used in the interchange format.
+fn journey_power_name(id: JourneyPowerId) -> Option<&'static str> {
+    Some(match id {
+        JourneyPowerId::FreezeTime => "freeze_time",
+        JourneyPowerId::StartDay => "start_day",
+        JourneyPowerId::StartNoon => "start_noon",
+        JourneyPowerId::StartNight => "start_night",
+        JourneyPowerId::StartMidnight => "start_midnight",
+        JourneyPowerId::Godmode => "godmode",
+        JourneyPowerId::WindStrength => "wind_strength",
+        JourneyPowerId::RainStrength => "rain_strength",
+        JourneyPowerId::TimeRate => "time_rate",
+        JourneyPowerId::FreezeRain => "freeze_rain",
+        JourneyPowerId::FreezeWind => "freeze_wind",
+        JourneyPowerId::FarPlacement => "far_placement",
+        JourneyPowerId::Difficulty => "difficulty",
+        JourneyPowerId::FreezeBiomeSpread => "freeze_biome_spread",
+        JourneyPowerId::Spawnrate => "spawnrate",
+        JourneyPowerId::Unknown => return None,
+    })
+}
+
+fn key<T: Meta>(registry: &MetaRegistry<T>, id: T::Id) -> MetaKey<T::Id> {
+    match registry.get(id) {
+        Some(meta) => MetaKey::Name(meta.internal_name().to_string()),
+        None => MetaKey::Id(id),
+    }
+}
+
+struct Exporter<'a> {
+    meta: &'a MetaBundle,
+}
+
+impl Exporter<'_> {
+    fn item(&self, item: &Item) -> ItemSlot {
+        if item.id == 0 {
+            return None;
+        }
+
+        Some(ItemDocument {
+            item: key(&self.meta.items, item.id),
+            stack: item.stack,
+            prefix: (item.prefix.id != 0).then(|| key(&self.meta.prefixes, item.prefix.id)),
+            favourited: item.favourited,
+        })
+    }
+
+    fn items(&self, items: &[Item]) -> Vec<ItemSlot> {
+        items.iter().map(|item| self.item(item)).collect()
+    }
+
+    fn loadout(&self, loadout: &Loadout) -> LoadoutDocument {
+        let armor = |i: usize| GearSlotDocument {
+            item: self.item(&loadout.armor[i]),
+            vanity: self.item(&loadout.vanity_armor[i]),
+            dye: self.item(&loadout.armor_dyes[i]),
+            hidden: loadout.hide_visual[i],
+        };
+        let accessory = |i: usize| GearSlotDocument {
+            item: self.item(&loadout.accessories[i]),
+            vanity: self.item(&loadout.vanity_accessories[i]),
+            dye: self.item(&loadout.accessory_dyes[i]),
+            hidden: loadout.hide_visual[ARMOR_COUNT + i],
+        };
+
+        LoadoutDocument {
+            head: armor(0),
+            body: armor(1),
+            legs: armor(2),
+            accessories: (0..ACCESSORY_COUNT).map(accessory).collect(),
+        }
+    }
+}
+
+struct Importer<'a> {
+    meta: &'a MetaBundle,
+}
+
+impl Importer<'_> {
+    fn resolve<T: Meta>(
+        &self,
+        registry: &MetaRegistry<T>,
+        kind: &'static str,
+        path: impl FnOnce() -> String,
+        key: &MetaKey<T::Id>,
+    ) -> Result<T::Id, InterchangeError> {
+        match key {
+            MetaKey::Id(id) => Ok(*id),
+            MetaKey::Name(name) => registry
+                .get_by_internal_name(name)
+                .map(|meta| meta.id())
+                .ok_or_else(|| InterchangeError::UnknownName {
+                    kind,
+                    name: name.clone(),
+                    path: path(),
+                }),
+        }
+    }
+
+    fn item(&self, path: &str, slot: &ItemSlot) -> Result<Item, InterchangeError> {
+        let Some(document) = slot else {
+            return Ok(Item::default());
+        };
+
+        let id = self.resolve(&self.meta.items, "item", || path.to_owned(), &document.item)?;
+        let prefix = match &document.prefix {
+            Some(prefix) => {
+                self.resolve(&self.meta.prefixes, "prefix", || path.to_owned(), prefix)?
+            }
+            None => 0,
+        };
+
+        Ok(Item {
+            id,
+            stack: document.stack,
+            prefix: Prefix { id: prefix },
+            favourited: document.favourited,
+            mod_item: None,
+        })
+    }
+
+    fn items(
+        &self,
+        name: &str,
+        slots: &[ItemSlot],
+        items: &mut [Item],
+    ) -> Result<(), InterchangeError> {
+        if slots.len() > items.len() {
+            return Err(InterchangeError::TooManySlots {
+                path: name.to_owned(),
+                found: slots.len(),
+                count: items.len(),
+            });
+        }
+
+        for (i, (slot, item)) in slots.iter().zip(items.iter_mut()).enumerate() {
+            *item = self.item(&format!("{name}[{i}]"), slot)?;
+        }
+        Ok(())
+    }
+
+    fn loadout(
+        &self,
+        name: &str,
+        document: &LoadoutDocument,
+        loadout: &mut Loadout,
+    ) -> Result<(), InterchangeError> {
+        if document.accessories.len() > ACCESSORY_COUNT {
+            return Err(InterchangeError::TooManySlots {
+                path: format!("{name}.accessories"),
+                found: document.accessories.len(),
+                count: ACCESSORY_COUNT,
+            });
+        }
+
+        let armor = [
+            ("head", &document.head),
+            ("body", &document.body),
+            ("legs", &document.legs),
+        ];
+        for (i, (field, gear)) in armor.into_iter().enumerate() {
+            let path = format!("{name}.{field}");
+            loadout.armor[i] = self.item(&format!("{path}.item"), &gear.item)?;
+            loadout.vanity_armor[i] = self.item(&format!("{path}.vanity"), &gear.vanity)?;
+            loadout.armor_dyes[i] = self.item(&format!("{path}.dye"), &gear.dye)?;
+            loadout.hide_visual[i] = gear.hidden;
+        }
+
+        for (i, gear) in document.accessories.iter().enumerate() {
+            let path = format!("{name}.accessories[{i}]");
+            loadout.accessories[i] = self.item(&format!("{path}.item"), &gear.item)?;
+            loadout.vanity_accessories[i] = self.item(&format!("{path}.vanity"), &gear.vanity)?;
+            loadout.accessory_dyes[i] = self.item(&format!("{path}.dye"), &gear.dye)?;
+            loadout.hide_visual[ARMOR_COUNT + i] = gear.hidden;
+        }
+
+        Ok(())
+    }
+}
+
+impl Player {
+    /// The player in the interchange format, with items, prefixes and buffs by internal name.
+    ///
+    /// tModLoader data isn't included.
+    pub fn to_document(&self, meta: &MetaBundle) -> PlayerDocument {
+        let exporter = Exporter { meta };
+
+        let equipment = |i: usize| EquipmentSlotDocument {
+            item: exporter.item(&self.equipment[i]),
+            dye: exporter.item(&self.equipment_dyes[i]),
+            hidden: self.hide_equipment[i],
+        };
+
+        PlayerDocument {
+            format_version: FORMAT_VERSION,
+            game_version: self.version,
+            revision: self.revision,
+            is_mobile: self.is_mobile,
+            favourited: self.favourited,
+            name: self.name.clone(),
+            difficulty: self.difficulty.into(),
+            team: self.team.into(),
+            playtime: self.playtime,
+            last_save: self.last_save,
+            appearance: AppearanceDocument {
+                male: self.male,
+                skin_variant: self.skin_variant,
+                hair_style: self.hair_style,
+                hair_dye: self.hair_dye,
+                hair_color: HexColor(self.hair_color),
+                skin_color: HexColor(self.skin_color),
+                eye_color: HexColor(self.eye_color),
+                shirt_color: HexColor(self.shirt_color),
+                undershirt_color: HexColor(self.undershirt_color),
+                pants_color: HexColor(self.pants_color),
+                shoe_color: HexColor(self.shoe_color),
+                voice_variant: self.voice_variant,
+                voice_pitch_offset: self.voice_pitch_offset,
+            },
+            stats: StatsDocument {
+                life: self.life,
+                max_life: self.max_life,
+                mana: self.mana,
+                max_mana: self.max_mana,
+                dead: self.dead,
+                respawn_timer: self.respawn_timer,
+                pve_deaths: self.pve_deaths,
+                pvp_deaths: self.pvp_deaths,
+                tax_money: self.tax_money,
+                angler_quests: self.angler_quests,
+                tavernkeep_quests: self.tavernkeep_quests,
+                golfer_score: self.golfer_score,
+            },
+            upgrades: UpgradesDocument {
+                demon_heart: self.demon_heart,
+                biome_torches: self.biome_torches,
+                biome_torches_enabled: self.biome_torches_enabled,
+                artisan_loaf: self.artisan_loaf,
+                vital_crystal: self.vital_crystal,
+                aegis_fruit: self.aegis_fruit,
+                arcane_crystal: self.arcane_crystal,
+                galaxy_pearl: self.galaxy_pearl,
+                gummy_worm: self.gummy_worm,
+                ambrosia: self.ambrosia,
+                defeated_ooa: self.defeated_ooa,
+                super_cart: self.super_cart,
+                super_cart_enabled: self.super_cart_enabled,
+            },
+            inventory: exporter.items(&self.inventory),
+            coins: exporter.items(&self.coins),
+            ammo: exporter.items(&self.ammo),
+            equipment: EquipmentDocument {
+                pet: equipment(0),
+                light_pet: equipment(1),
+                minecart: equipment(2),
+                mount: equipment(3),
+                hook: equipment(4),
+            },
+            current_loadout: self.current_loadout_index,
+            loadouts: self.loadouts.iter().map(|l| exporter.loadout(l)).collect(),
+            piggy_bank: exporter.items(&self.piggy_bank),
+            safe: exporter.items(&self.safe),
+            defenders_forge: exporter.items(&self.defenders_forge),
+            void_vault: exporter.items(&self.void_vault),
+            void_vault_enabled: self.void_vault_enabled,
+            temporary_slots: TemporarySlotsDocument {
+                mouse: exporter.item(&self.temporary_slots[0]),
+                item_by_index: exporter.item(&self.temporary_slots[1]),
+                guide: exporter.item(&self.temporary_slots[2]),
+                reforge: exporter.item(&self.temporary_slots[3]),
+            },
+            pending_refunds: self
+                .pending_refunds
+                .iter()
+                .filter_map(|item| exporter.item(item))
+                .collect(),
+            buffs: self
+                .buffs
+                .iter()
+                .filter(|buff| buff.id != 0)
+                .map(|buff| BuffDocument {
+                    buff: key(&meta.buffs, buff.id),
+                    time: buff.time,
+                })
+                .collect(),
+            spawnpoints: self
+                .spawnpoints
+                .iter()
+                .map(|spawnpoint| SpawnpointDocument {
+                    world_id: spawnpoint.id,
+                    world_name: spawnpoint.name.clone(),
+                    x: spawnpoint.x,
+                    y: spawnpoint.y,
+                })
+                .collect(),
+            research: self
+                .research
+                .iter()
+                .map(|research| ResearchDocument {
+                    item: research.internal_name.to_string(),
+                    stack: research.stack,
+                })
+                .collect(),
+            share_research: self.share_research,
+            journey_powers: self
+                .journey_powers
+                .powers
+                .iter()
+                .map(|power| JourneyPowerDocument {
+                    power: match journey_power_name(power.power_id()) {
+                        Some(name) => MetaKey::Name(name.to_owned()),
+                        None => MetaKey::Id(power.id),
+                    },
+                    value: match &power.value {
+                        JourneyPowerValue::None => JourneyPowerValueDocument::None(()),
+                        JourneyPowerValue::Toggle(value) => {
+                            JourneyPowerValueDocument::Toggle(*value)
+                        }
+                        JourneyPowerValue::Slider(value) => {
+                            JourneyPowerValueDocument::Slider(*value)
+                        }
+                        JourneyPowerValue::Raw(bytes) => {
+                            JourneyPowerValueDocument::Raw(bytes.clone())
+                        }
+                    },
+                })
+                .collect(),
+            interface: InterfaceDocument {
+                locked_hotbar: self.locked_hotbar,
+                hide_cellphone_info: self.hide_cellphone_info.to_vec(),
+                dpad_bindings: self.dpad_bindings.to_vec(),
+                builder_accessory_status: self.builder_accessory_status.to_vec(),
+                one_time_dialogues_seen: self.one_time_dialogues_seen.clone(),
+            },
+        }
+    }
+
+    /// Reads a player from the interchange format.
+    ///
+    /// Lists may be shorter than the player's, in which case the remaining slots are empty
+    /// or keep their default, but not longer.
+    pub fn from_document(document: &PlayerDocument, meta: &MetaBundle) -> anyhow::Result<Self> {
+        if document.format_version > FORMAT_VERSION {
+            return Err(InterchangeError::UnsupportedFormat(document.format_version.into()).into());
+        }
+        if document.game_version > CURRENT_VERSION {
+            return Err(InterchangeError::PostDated(document.game_version).into());
+        }
+
+        let importer = Importer { meta };
+        let mut player = Player {
+            version: document.game_version,
+            revision: document.revision,
+            is_mobile: document.is_mobile,
+            favourited: document.favourited,
+            name: document.name.clone(),
+            difficulty: document.difficulty.into(),
+            team: document.team.into(),
+            playtime: document.playtime,
+            last_save: document.last_save,
+            current_loadout_index: document.current_loadout,
+            void_vault_enabled: document.void_vault_enabled,
+            share_research: document.share_research,
+            ..Default::default()
+        };
+
+        let appearance = &document.appearance;
+        player.male = appearance.male;
+        player.skin_variant = appearance.skin_variant;
+        player.hair_style = appearance.hair_style;
+        player.hair_dye = appearance.hair_dye;
+        player.hair_color = appearance.hair_color.0;
+        player.skin_color = appearance.skin_color.0;
+        player.eye_color = appearance.eye_color.0;
+        player.shirt_color = appearance.shirt_color.0;
+        player.undershirt_color = appearance.undershirt_color.0;
+        player.pants_color = appearance.pants_color.0;
+        player.shoe_color = appearance.shoe_color.0;
+        player.voice_variant = appearance.voice_variant;
+        player.voice_pitch_offset = appearance.voice_pitch_offset;
+
+        let stats = &document.stats;
+        player.life = stats.life;
+        player.max_life = stats.max_life;
+        player.mana = stats.mana;
+        player.max_mana = stats.max_mana;
+        player.dead = stats.dead;
+        player.respawn_timer = stats.respawn_timer;
+        player.pve_deaths = stats.pve_deaths;
+        player.pvp_deaths = stats.pvp_deaths;
+        player.tax_money = stats.tax_money;
+        player.angler_quests = stats.angler_quests;
+        player.tavernkeep_quests = stats.tavernkeep_quests;
+        player.golfer_score = stats.golfer_score;
+
+        let upgrades = &document.upgrades;
+        player.demon_heart = upgrades.demon_heart;
+        player.biome_torches = upgrades.biome_torches;
+        player.biome_torches_enabled = upgrades.biome_torches_enabled;
+        player.artisan_loaf = upgrades.artisan_loaf;
+        player.vital_crystal = upgrades.vital_crystal;
+        player.aegis_fruit = upgrades.aegis_fruit;
+        player.arcane_crystal = upgrades.arcane_crystal;
+        player.galaxy_pearl = upgrades.galaxy_pearl;
+        player.gummy_worm = upgrades.gummy_worm;
+        player.ambrosia = upgrades.ambrosia;
+        player.defeated_ooa = upgrades.defeated_ooa;
+        player.super_cart = upgrades.super_cart;
+        player.super_cart_enabled = upgrades.super_cart_enabled;
+
+        importer.items("inventory", &document.inventory, &mut player.inventory)?;
+        importer.items("coins", &document.coins, &mut player.coins)?;
+        importer.items("ammo", &document.ammo, &mut player.ammo)?;
+        importer.items("piggy_bank", &document.piggy_bank, &mut player.piggy_bank)?;
+        importer.items("safe", &document.safe, &mut player.safe)?;
+        importer.items(
+            "defenders_forge",
+            &document.defenders_forge,
+            &mut player.defenders_forge,
+        )?;
+        importer.items("void_vault", &document.void_vault, &mut player.void_vault)?;
+
+        let equipment = &document.equipment;
+        let slots = [
+            ("pet", &equipment.pet),
+            ("light_pet", &equipment.light_pet),
+            ("minecart", &equipment.minecart),
+            ("mount", &equipment.mount),
+            ("hook", &equipment.hook),
+        ];
+        for (i, (field, slot)) in slots.into_iter().enumerate() {
+            let path = format!("equipment.{field}");
+            player.equipment[i] = importer.item(&format!("{path}.item"), &slot.item)?;
+            player.equipment_dyes[i] = importer.item(&format!("{path}.dye"), &slot.dye)?;
+            player.hide_equipment[i] = slot.hidden;
+        }
+
+        if document.loadouts.len() > player.loadouts.len() {
+            return Err(InterchangeError::TooManySlots {
+                path: "loadouts".to_owned(),
+                found: document.loadouts.len(),
+                count: player.loadouts.len(),
+            }
+            .into());
+        }
+        for (i, (loadout_document, loadout)) in document
+            .loadouts
+            .iter()
+            .zip(player.loadouts.iter_mut())
+            .enumerate()
+        {
+            importer.loadout(&format!("loadouts[{i}]"), loadout_document, loadout)?;
+        }
+
+        let temporary = &document.temporary_slots;
+        let slots = [
+            ("mouse", &temporary.mouse),
+            ("item_by_index", &temporary.item_by_index),
+            ("guide", &temporary.guide),
+            ("reforge", &temporary.reforge),
+        ];
+        for (i, (field, slot)) in slots.into_iter().enumerate() {
+            player.temporary_slots[i] = importer.item(&format!("temporary_slots.{field}"), slot)?;
+        }
+
+        player.pending_refunds = document
+            .pending_refunds
+            .iter()
+            .enumerate()
+            .map(|(i, item)| importer.item(&format!("pending_refunds[{i}]"), &Some(item.clone())))
+            .collect::<Result<_, _>>()?;
+
+        if document.buffs.len() > player.buffs.len() {
+            return Err(InterchangeError::TooManySlots {
+                path: "buffs".to_owned(),
+                found: document.buffs.len(),
+                count: player.buffs.len(),
+            }
+            .into());
+        }
+        for (i, (buff_document, buff)) in document
+            .buffs
+            .iter()
+            .zip(player.buffs.iter_mut())
+            .enumerate()
+        {
+            *buff = Buff {
+                id: importer.resolve(
+                    &meta.buffs,
+                    "buff",
+                    || format!("buffs[{i}]"),
+                    &buff_document.buff,
+                )?,
+                time: buff_document.time,
+            };
+        }
+
+        player.spawnpoints = document
+            .spawnpoints
+            .iter()
+            .map(|spawnpoint| Spawnpoint {
+                id: spawnpoint.world_id,
+                x: spawnpoint.x,
+                y: spawnpoint.y,
+                name: spawnpoint.world_name.clone(),
+            })
+            .collect();
+
+        player.research = document
+            .research
+            .iter()
+            .map(|research| ResearchItem {
+                internal_name: research.item.clone().into(),
+                stack: research.stack,
+            })
+            .collect();
+
+        let mut powers = Vec::with_capacity(document.journey_powers.len());
+        for (i, power) in document.journey_powers.iter().enumerate() {
+            let id = match &power.power {
+                MetaKey::Id(id) => *id,
+                MetaKey::Name(name) => (0..u16::from(JourneyPowerId::Unknown))
+                    .find(|id| journey_power_name(JourneyPowerId::from(*id)) == Some(name))
+                    .ok_or_else(|| InterchangeError::UnknownName {
+                        kind: "journey power",
+                        name: name.clone(),
+                        path: format!("journey_powers[{i}]"),
+                    })?,
+            };
+            let value = match &power.value {
+                JourneyPowerValueDocument::None(()) => JourneyPowerValue::None,
+                JourneyPowerValueDocument::Toggle(value) => JourneyPowerValue::Toggle(*value),
+                JourneyPowerValueDocument::Slider(value) => JourneyPowerValue::Slider(*value),
+                JourneyPowerValueDocument::Raw(bytes) => JourneyPowerValue::Raw(bytes.clone()),
+            };
+            powers.push(JourneyPower { id, value });
+        }
+        player.journey_powers = JourneyPowers { powers };
+        player
+            .journey_powers
+            .check()
+            .map_err(|source| InterchangeError::JourneyPowers { source })?;
+
+        let interface = &document.interface;
+        player.locked_hotbar = interface.locked_hotbar;
+        copy_values(
+            "interface.hide_cellphone_info",
+            &interface.hide_cellphone_info,
+            &mut player.hide_cellphone_info,
+        )?;
+        copy_values(
+            "interface.dpad_bindings",
+            &interface.dpad_bindings,
+            &mut player.dpad_bindings,
+        )?;
+        copy_values(
+            "interface.builder_accessory_status",
+            &interface.builder_accessory_status,
+            &mut player.builder_accessory_status,
+        )?;
+        player.one_time_dialogues_seen = interface.one_time_dialogues_seen.clone();
+
+        Ok(player)
+    }
+
+    /// Writes the player in the interchange format, see `to_document`.
+    pub fn export_json(&self, meta: &MetaBundle) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(&self.to_document(meta))?)
+    }
+
+    /// Reads a player written in the interchange format, see `from_document`.
+    ///
+    /// The format version is checked first, so documents from a newer terra-rs fail with
+    /// `InterchangeError::UnsupportedFormat` rather than a confusing parse error.
+    pub fn import_json(json: &str, meta: &MetaBundle) -> anyhow::Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let format_version = value
+            .get("format_version")
+            .and_then(|v| v.as_u64())
+            .ok_or(InterchangeError::MissingFormatVersion)?;
+        if format_version > u64::from(FORMAT_VERSION) {
+            return Err(InterchangeError::UnsupportedFormat(format_version).into());
+        }
+
+        let document: PlayerDocument = serde_json::from_value(value)?;
+        Self::from_document(&document, meta)
+    }
+}
+
+fn copy_values<T: Copy>(path: &str, values: &[T], target: &mut [T]) -> anyhow::Result<()> {
+    if values.len() > target.len() {
+        return Err(InterchangeError::TooManySlots {
+            path: path.to_owned(),
+            found: values.len(),
+            count: target.len(),
+        }
+        .into());
+    }
+
+    target[..values.len()].copy_from_slice(values);
+    Ok(())
+}
@@ -0,0 +1,320 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Color, Difficulty, Team};
+
+/// An item, prefix, buff or journey power, by internal name when terra-rs knows it and by
+/// id when it doesn't.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MetaKey<Id> {
+    Name(String),
+    Id(Id),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemDocument {
+    pub item: MetaKey<i32>,
+    pub stack: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<MetaKey<u8>>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub favourited: bool,
+}
+
+/// An empty slot is `null`.
+pub type ItemSlot = Option<ItemDocument>;
+
+/// An armor or accessory slot in a loadout, with the vanity and dye slots next to it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GearSlotDocument {
+    pub item: ItemSlot,
+    pub vanity: ItemSlot,
+    pub dye: ItemSlot,
+    /// Whether the item's visuals are hidden.
+    pub hidden: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LoadoutDocument {
+    pub head: GearSlotDocument,
+    pub body: GearSlotDocument,
+    pub legs: GearSlotDocument,
+    pub accessories: Vec<GearSlotDocument>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EquipmentSlotDocument {
+    pub item: ItemSlot,
+    pub dye: ItemSlot,
+    pub hidden: bool,
+}
+
+/// The equipment slots, which are shared between loadouts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EquipmentDocument {
+    pub pet: EquipmentSlotDocument,
+    pub light_pet: EquipmentSlotDocument,
+    pub minecart: EquipmentSlotDocument,
+    pub mount: EquipmentSlotDocument,
+    pub hook: EquipmentSlotDocument,
+}
+
+/// Items the player was holding when they saved, which the game gives back on load.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TemporarySlotsDocument {
+    pub mouse: ItemSlot,
+    pub item_by_index: ItemSlot,
+    pub guide: ItemSlot,
+    pub reforge: ItemSlot,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuffDocument {
+    pub buff: MetaKey<i32>,
+    /// The time left, in ticks.
+    pub time: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpawnpointDocument {
+    pub world_id: i32,
+    pub world_name: String,
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResearchDocument {
+    /// Research is always saved by internal name, even for items terra-rs doesn't know.
+    pub item: String,
+    pub stack: i32,
+}
+
+/// A power's value: `null` for buttons, a bool for toggles, a number from 0 to 1 for
+/// sliders, and the saved bytes for powers terra-rs doesn't recognise.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum JourneyPowerValueDocument {
+    Toggle(bool),
+    Slider(f32),
+    Raw(Vec<u8>),
+    None(()),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JourneyPowerDocument {
+    pub power: MetaKey<u16>,
+    pub value: JourneyPowerValueDocument,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DifficultyName {
+    Classic,
+    Mediumcore,
+    Hardcore,
+    Journey,
+    Unknown,
+}
+
+impl From<Difficulty> for DifficultyName {
+    fn from(value: Difficulty) -> Self {
+        match value {
+            Difficulty::Classic => Self::Classic,
+            Difficulty::Mediumcore => Self::Mediumcore,
+            Difficulty::Hardcore => Self::Hardcore,
+            Difficulty::Journey => Self::Journey,
+            Difficulty::Unknown => Self::Unknown,
+        }
+    }
+}
+
+impl From<DifficultyName> for Difficulty {
+    fn from(value: DifficultyName) -> Self {
+        match value {
+            DifficultyName::Classic => Self::Classic,
+            DifficultyName::Mediumcore => Self::Mediumcore,
+            DifficultyName::Hardcore => Self::Hardcore,
+            DifficultyName::Journey => Self::Journey,
+            DifficultyName::Unknown => Self::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TeamName {
+    None,
+    Red,
+    Green,
+    Blue,
+    Yellow,
+    Pink,
+    Unknown,
+}
+
+impl From<Team> for TeamName {
+    fn from(value: Team) -> Self {
+        match value {
+            Team::None => Self::None,
+            Team::Red => Self::Red,
+            Team::Green => Self::Green,
+            Team::Blue => Self::Blue,
+            Team::Yellow => Self::Yellow,
+            Team::Pink => Self::Pink,
+            Team::Unknown => Self::Unknown,
+        }
+    }
+}
+
+impl From<TeamName> for Team {
+    fn from(value: TeamName) -> Self {
+        match value {
+            TeamName::None => Self::None,
+            TeamName::Red => Self::Red,
+            TeamName::Green => Self::Green,
+            TeamName::Blue => Self::Blue,
+            TeamName::Yellow => Self::Yellow,
+            TeamName::Pink => Self::Pink,
+            TeamName::Unknown => Self::Unknown,
+        }
+    }
+}
+
+/// A colour written as `#rrggbb`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HexColor(pub Color);
+
+impl Serialize for HexColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let [r, g, b] = self.0;
+        serializer.serialize_str(&format!("#{r:02x}{g:02x}{b:02x}"))
+    }
+}
+
+impl<'de> Deserialize<'de> for HexColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        let invalid = || serde::de::Error::custom(format!("`{string}` isn't a #rrggbb colour"));
+
+        let hex = string.strip_prefix('#').filter(|h| h.len() == 6);
+        let hex = hex.ok_or_else(invalid)?;
+        let channel = |i: usize| {
+            hex.get(i..i + 2)
+                .and_then(|c| u8::from_str_radix(c, 16).ok())
+                .ok_or_else(invalid)
+        };
+
+        Ok(Self([channel(0)?, channel(2)?, channel(4)?]))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AppearanceDocument {
+    pub male: bool,
+    pub skin_variant: u8,
+    pub hair_style: i32,
+    pub hair_dye: u8,
+    pub hair_color: HexColor,
+    pub skin_color: HexColor,
+    pub eye_color: HexColor,
+    pub shirt_color: HexColor,
+    pub undershirt_color: HexColor,
+    pub pants_color: HexColor,
+    pub shoe_color: HexColor,
+    pub voice_variant: u8,
+    pub voice_pitch_offset: f32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StatsDocument {
+    pub life: i32,
+    pub max_life: i32,
+    pub mana: i32,
+    pub max_mana: i32,
+    pub dead: bool,
+    /// In ticks.
+    pub respawn_timer: i32,
+    pub pve_deaths: i32,
+    pub pvp_deaths: i32,
+    /// In copper coins.
+    pub tax_money: i32,
+    pub angler_quests: i32,
+    pub tavernkeep_quests: i32,
+    pub golfer_score: i32,
+}
+
+/// Permanent upgrades from consumables, and whether the toggleable ones are enabled.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpgradesDocument {
+    pub demon_heart: bool,
+    pub biome_torches: bool,
+    pub biome_torches_enabled: bool,
+    pub artisan_loaf: bool,
+    pub vital_crystal: bool,
+    pub aegis_fruit: bool,
+    pub arcane_crystal: bool,
+    pub galaxy_pearl: bool,
+    pub gummy_worm: bool,
+    pub ambrosia: bool,
+    pub defeated_ooa: bool,
+    pub super_cart: bool,
+    pub super_cart_enabled: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InterfaceDocument {
+    pub locked_hotbar: bool,
+    pub hide_cellphone_info: Vec<bool>,
+    pub dpad_bindings: Vec<i32>,
+    /// See `BUILDER_ACCESSORY_COUNT` for which accessory each entry is.
+    pub builder_accessory_status: Vec<i32>,
+    pub one_time_dialogues_seen: Vec<String>,
+}
+
+/// A player in terra-rs's interchange format, described by `PLAYER_SCHEMA`.
+///
+/// Unlike `Player`'s own serialization, this is kept stable between terra-rs versions.
+/// Changes bump `format_version`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerDocument {
+    pub format_version: u32,
+    /// The version of the game the player was saved by.
+    pub game_version: i32,
+    pub revision: u32,
+    pub is_mobile: bool,
+    pub favourited: u64,
+    pub name: String,
+    pub difficulty: DifficultyName,
+    pub team: TeamName,
+    /// In ticks of 100 nanoseconds.
+    pub playtime: i64,
+    /// In ticks of 100 nanoseconds since 0001-01-01.
+    pub last_save: i64,
+    pub appearance: AppearanceDocument,
+    pub stats: StatsDocument,
+    pub upgrades: UpgradesDocument,
+    pub inventory: Vec<ItemSlot>,
+    pub coins: Vec<ItemSlot>,
+    pub ammo: Vec<ItemSlot>,
+    pub equipment: EquipmentDocument,
+    pub current_loadout: i32,
+    pub loadouts: Vec<LoadoutDocument>,
+    pub piggy_bank: Vec<ItemSlot>,
+    pub safe: Vec<ItemSlot>,
+    pub defenders_forge: Vec<ItemSlot>,
+    pub void_vault: Vec<ItemSlot>,
+    pub void_vault_enabled: bool,
+    pub temporary_slots: TemporarySlotsDocument,
+    pub pending_refunds: Vec<ItemDocument>,
+    pub buffs: Vec<BuffDocument>,
+    pub spawnpoints: Vec<SpawnpointDocument>,
+    pub research: Vec<ResearchDocument>,
+    pub share_research: bool,
+    pub journey_powers: Vec<JourneyPowerDocument>,
+    pub interface: InterfaceDocument,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
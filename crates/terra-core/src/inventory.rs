@@ -0,0 +1,294 @@
+use std::{cmp::Reverse, fmt::Display};
+
+use crate::{meta::MetaRegistry, Item, ItemMeta, ItemRarity, ItemType, Player, INVENTORY_STRIDE};
+
+#[derive(thiserror::Error, Debug)]
+pub enum InventoryError {
+    #[error("Version {version} players don't have a {bank}.")]
+    MissingBank { bank: Bank, version: i32 },
+}
+
+/// A bank the inventory can be quick stacked into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+#[cfg_attr(
+    any(feature = "serialize", feature = "deserialize"),
+    serde(rename_all = "snake_case")
+)]
+pub enum Bank {
+    PiggyBank,
+    Safe,
+    DefendersForge,
+    VoidVault,
+}
+
+impl Bank {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [
+            Self::PiggyBank,
+            Self::Safe,
+            Self::DefendersForge,
+            Self::VoidVault,
+        ]
+        .into_iter()
+    }
+
+    /// The version the bank was added in.
+    fn since(self) -> i32 {
+        match self {
+            Self::PiggyBank => 0,
+            Self::Safe => 20,
+            Self::DefendersForge => 182,
+            Self::VoidVault => 198,
+        }
+    }
+
+    /// The bank's items, alongside the inventory.
+    fn with_inventory(self, player: &mut Player) -> (&mut [Item], &mut [Item]) {
+        let bank = match self {
+            Self::PiggyBank => &mut player.piggy_bank,
+            Self::Safe => &mut player.safe,
+            Self::DefendersForge => &mut player.defenders_forge,
+            Self::VoidVault => &mut player.void_vault,
+        };
+        (bank, &mut player.inventory)
+    }
+}
+
+impl Display for Bank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PiggyBank => write!(f, "Piggy Bank"),
+            Self::Safe => write!(f, "Safe"),
+            Self::DefendersForge => write!(f, "Defender's Forge"),
+            Self::VoidVault => write!(f, "Void Vault"),
+        }
+    }
+}
+
+/// The groups the game's sort puts items in, in the order it puts them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SortGroup {
+    Melee,
+    Ranged,
+    Magic,
+    Summon,
+    Ammo,
+    Pickaxes,
+    Axes,
+    Hammers,
+    FishingPoles,
+    Armor,
+    Vanity,
+    Accessories,
+    LifePotions,
+    ManaPotions,
+    Consumables,
+    Materials,
+    Tiles,
+    Walls,
+    Other,
+}
+
+impl SortGroup {
+    fn of(meta: &ItemMeta) -> Self {
+        let has = |power: Option<i32>| power.is_some_and(|p| p > 0);
+
+        // Tools deal damage too, so they're checked before weapons
+        if has(meta.pickaxe_power) {
+            return Self::Pickaxes;
+        }
+        if has(meta.axe_power) {
+            return Self::Axes;
+        }
+        if has(meta.hammer_power) {
+            return Self::Hammers;
+        }
+        if has(meta.fishing_power) {
+            return Self::FishingPoles;
+        }
+
+        match meta.item_type {
+            Some(ItemType::Melee) => Self::Melee,
+            Some(ItemType::Ranged) => Self::Ranged,
+            Some(ItemType::Magic) => Self::Magic,
+            Some(ItemType::Summon) => Self::Summon,
+            Some(ItemType::Ammo) => Self::Ammo,
+            Some(ItemType::HeadArmor | ItemType::BodyArmor | ItemType::LegArmor) => Self::Armor,
+            Some(ItemType::Vanity) => Self::Vanity,
+            Some(ItemType::Accessory) => Self::Accessories,
+            _ if has(meta.heal_life) => Self::LifePotions,
+            _ if has(meta.heal_mana) => Self::ManaPotions,
+            _ if meta.is_material.is_some_and(|m| m) => Self::Materials,
+            Some(ItemType::Tile) => Self::Tiles,
+            Some(ItemType::Wall) => Self::Walls,
+            _ if meta.is_consumable.is_some_and(|c| c) => Self::Consumables,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Where the game's sort puts an item: by group, then rarest first, then by id, with the
+/// largest stacks first.
+fn sort_key(
+    item: &Item,
+    meta: &MetaRegistry<ItemMeta>,
+) -> (SortGroup, Reverse<i32>, i32, Reverse<i32>) {
+    let (group, rarity) = match meta.get(item.id) {
+        Some(meta) => {
+            let rarity = match meta.rarity {
+                ItemRarity::Unknown => ItemRarity::White,
+                rarity => rarity,
+            };
+            (SortGroup::of(meta), rarity as i32)
+        }
+        None => (SortGroup::Other, ItemRarity::White as i32),
+    };
+    (group, Reverse(rarity), item.id, Reverse(item.stack))
+}
+
+/// Whether `other` can be added to `item`'s stack. tModLoader items are left alone, as
+/// their data may differ.
+fn stacks_with(item: &Item, other: &Item, meta: &MetaRegistry<ItemMeta>) -> bool {
+    item.id != 0
+        && item.id == other.id
+        && item.prefix.id == other.prefix.id
+        && item.mod_item.is_none()
+        && other.mod_item.is_none()
+        && meta
+            .get(item.id)
+            .is_some_and(|m| m.effective_max_stack() > 1)
+}
+
+/// Tops up partial stacks in the given slots from later ones, emptying the later slots
+/// where it can.
+fn merge_stacks(items: &mut [Item], slots: &[usize], meta: &MetaRegistry<ItemMeta>) {
+    for (n, &i) in slots.iter().enumerate() {
+        let Some(max_stack) = meta.get(items[i].id).map(|m| m.effective_max_stack()) else {
+            continue;
+        };
+
+        for &j in &slots[n + 1..] {
+            if items[i].stack >= max_stack {
+                break;
+            }
+            if !stacks_with(&items[i], &items[j], meta) {
+                continue;
+            }
+
+            let moved = (max_stack - items[i].stack).min(items[j].stack);
+            items[i].stack += moved;
+            items[j].stack -= moved;
+            if items[j].stack <= 0 {
+                items[j] = Item::default();
+            }
+        }
+    }
+}
+
+impl Player {
+    /// The inventory slots, out of those the player's version has, which sorting and
+    /// stacking may change. Favourited items are never moved, and neither is the hotbar
+    /// when `hotbar` is false.
+    fn unfavourited_slots(&self, hotbar: bool) -> Vec<usize> {
+        let inventory_count = if self.version >= 58 { 50 } else { 40 };
+        let start = if hotbar { 0 } else { INVENTORY_STRIDE };
+
+        (start..inventory_count)
+            .filter(|&i| !self.inventory[i].favourited)
+            .collect()
+    }
+
+    /// Sorts the inventory like the game's sort button, merging partial stacks first.
+    ///
+    /// As in game, the hotbar, coins and ammo aren't sorted, and favourited items stay
+    /// where they are.
+    pub fn sort_inventory(&mut self, meta: &MetaRegistry<ItemMeta>) {
+        let slots = self.unfavourited_slots(false);
+        merge_stacks(&mut self.inventory, &slots, meta);
+
+        let mut items: Vec<Item> = slots
+            .iter()
+            .map(|&i| std::mem::take(&mut self.inventory[i]))
+            .filter(|item| item.id != 0)
+            .collect();
+        items.sort_by_cached_key(|item| sort_key(item, meta));
+
+        let mut items = items.into_iter();
+        for &i in &slots {
+            self.inventory[i] = items.next().unwrap_or_default();
+        }
+    }
+
+    /// Merges partial stacks in the inventory up to each item's
+    /// `ItemMeta::effective_max_stack`, keeping the earliest slots filled.
+    ///
+    /// Favourited items are left alone, as is the hotbar while it's locked.
+    pub fn merge_inventory_stacks(&mut self, meta: &MetaRegistry<ItemMeta>) {
+        let slots = self.unfavourited_slots(!self.locked_hotbar);
+        merge_stacks(&mut self.inventory, &slots, meta);
+    }
+
+    /// Moves inventory items into a bank which already holds the same item, like the game's
+    /// quick stack button. Existing stacks are topped up first, then the rest goes in empty
+    /// slots.
+    ///
+    /// As in game, the hotbar, coins, ammo and favourited items aren't moved. Returns the
+    /// number of inventory slots that items were moved out of.
+    pub fn quick_stack(
+        &mut self,
+        bank: Bank,
+        meta: &MetaRegistry<ItemMeta>,
+    ) -> Result<usize, InventoryError> {
+        if self.version < bank.since() {
+            return Err(InventoryError::MissingBank {
+                bank,
+                version: self.version,
+            });
+        }
+
+        let bank_count = if self.version >= 58 { 40 } else { 20 };
+        let slots = self.unfavourited_slots(false);
+        let (bank_items, inventory) = bank.with_inventory(self);
+        let bank_items = &mut bank_items[..bank_count];
+
+        let mut moved = 0;
+        for i in slots {
+            let item = &mut inventory[i];
+            if item.id == 0 || !bank_items.iter().any(|b| b.id == item.id) {
+                continue;
+            }
+            let stack = item.stack;
+            let max_stack = meta
+                .get(item.id)
+                .map(|m| m.effective_max_stack())
+                .unwrap_or(item.stack);
+
+            for existing in bank_items.iter_mut() {
+                if item.stack <= 0 {
+                    break;
+                }
+                if stacks_with(existing, item, meta) && existing.stack < max_stack {
+                    let amount = (max_stack - existing.stack).min(item.stack);
+                    existing.stack += amount;
+                    item.stack -= amount;
+                }
+            }
+
+            if item.stack > 0 {
+                if let Some(empty) = bank_items.iter_mut().find(|b| b.id == 0) {
+                    *empty = std::mem::take(item);
+                }
+            }
+            if item.stack <= 0 {
+                *item = Item::default();
+            }
+            if item.id == 0 || item.stack != stack {
+                moved += 1;
+            }
+        }
+
+        Ok(moved)
+    }
+}
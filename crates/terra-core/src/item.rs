@@ -1,9 +1,11 @@
 mod item_data;
 mod item_meta;
+mod item_stats;
 mod research_item;
 
 pub use item_data::{Item, ItemError};
-pub use item_meta::{ItemMeta, ItemType};
+pub use item_meta::{ItemMeta, ItemType, LegacyName};
+pub use item_stats::ItemStats;
 pub use research_item::ResearchItem;
 
 #[repr(i32)]
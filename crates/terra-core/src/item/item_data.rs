@@ -4,8 +4,9 @@ use byteorder::{ReadBytesExt, WriteBytesExt, LE};
 
 use crate::{
     ext::{TerraReadExt, TerraWriteExt},
-    meta::Meta,
-    ItemMeta, Prefix,
+    meta::MetaRegistry,
+    parse::FieldContext,
+    ItemMeta, ModItem, Prefix,
 };
 
 #[derive(thiserror::Error, Debug)]
@@ -22,89 +23,16 @@ pub struct Item {
     pub stack: i32,
     pub prefix: Prefix,
     pub favourited: bool,
+    /// The item a tModLoader player has in this slot, which is only saved to the `.tplr`.
+    #[serde(skip)]
+    pub mod_item: Option<Box<ModItem>>,
 }
 
 impl Item {
-    fn legacy_lookup(version: i32, legacy_name: &str) -> &str {
-        if version <= 4 {
-            if legacy_name == "Cobalt Helmet" {
-                "Jungle Hat"
-            } else if legacy_name == "Cobalt Breastplate" {
-                "Jungle Shirt"
-            } else if legacy_name == "Cobalt Greaves" {
-                "Jungle Pants"
-            } else {
-                legacy_name
-            }
-        } else if version <= 20 {
-            if legacy_name == "Gills potion" {
-                "Gills Potion"
-            } else if legacy_name == "Thorn Chakrum" {
-                "Thorm Chakram"
-            } else if legacy_name == "Ball 'O Hurt" {
-                "Ball O' Hurt"
-            } else {
-                legacy_name
-            }
-        } else if version <= 41 && legacy_name == "Iron Chain" {
-            "Chain"
-        } else if version <= 44 && legacy_name == "Orb of Light" {
-            "Shadow Orb"
-        } else if version <= 46 {
-            if legacy_name == "Black Dye" {
-                "Black Thread"
-            } else if legacy_name == "Green Dye" {
-                "Green Thread"
-            } else {
-                legacy_name
-            }
-        } else {
-            legacy_name
-        }
-    }
-
-    fn reverse_legacy_lookup(version: i32, name: &str) -> &str {
-        if version <= 4 {
-            if name == "Jungle Hat" {
-                "Cobalt Helmet"
-            } else if name == "Jungle Shirt" {
-                "Cobalt Breastplate"
-            } else if name == "Jungle Pants" {
-                "Cobalt Greaves"
-            } else {
-                name
-            }
-        } else if version <= 20 {
-            if name == "Gills Potion" {
-                "Gills potion"
-            } else if name == "Thorn Chakram" {
-                "Thork Chakrum"
-            } else if name == "Ball O' Hurt" {
-                "Ball 'O Hurt"
-            } else {
-                name
-            }
-        } else if version <= 41 && name == "Chain" {
-            "Iron Chain"
-        } else if version <= 44 && name == "Shadow Orb" {
-            "Orb of Light"
-        } else if version <= 46 {
-            if name == "Black Thread" {
-                "Black Dye"
-            } else if name == "Green Thread" {
-                "Green Dye"
-            } else {
-                name
-            }
-        } else {
-            name
-        }
-    }
-
     pub fn load(
         &mut self,
         reader: &mut dyn Read,
-        item_meta: &[ItemMeta],
+        item_meta: &MetaRegistry<ItemMeta>,
         id: bool,
         internal_name: bool,
         stack: bool,
@@ -116,23 +44,23 @@ impl Item {
         }
 
         if id {
-            self.id = reader.read_i32::<LE>()?;
+            self.id = reader.read_i32::<LE>().field("id")?;
         }
         if internal_name {
-            let internal_name = reader.read_lpstring()?;
+            let internal_name = reader.read_lpstring().field("internal_name")?;
 
-            if let Some(item) = ItemMeta::get_by_internal_name(item_meta, &internal_name) {
+            if let Some(item) = item_meta.get_by_internal_name(&internal_name) {
                 self.id = item.id;
             }
         }
         if stack {
-            self.stack = reader.read_i32::<LE>()?;
+            self.stack = reader.read_i32::<LE>().field("stack")?;
         }
         if prefix {
-            self.prefix.load(reader)?;
+            self.prefix.load(reader).field("prefix")?;
         }
         if favourited {
-            self.favourited = reader.read_bool()?;
+            self.favourited = reader.read_bool().field("favourited")?;
         }
 
         if self.id != 0 && self.stack == 0 {
@@ -142,33 +70,49 @@ impl Item {
         Ok(())
     }
 
+    /// Loads an item saved by name, as players did before version 38.
     pub fn load_from_legacy_name(
         &mut self,
         reader: &mut dyn Read,
-        item_meta: &[ItemMeta],
+        item_meta: &MetaRegistry<ItemMeta>,
         version: i32,
         stack: bool,
+        prefix: bool,
     ) -> anyhow::Result<()> {
-        let legacy_name = reader.read_lpstring()?;
-        let name = Self::legacy_lookup(version, &legacy_name);
+        let name = reader.read_lpstring().field("name")?;
+        self.id = ItemMeta::get_by_legacy_name(item_meta, version, &name).map_or(0, |m| m.id);
 
         if stack {
-            self.stack = reader.read_i32::<LE>()?
+            self.stack = reader.read_i32::<LE>().field("stack")?;
+        }
+        if prefix {
+            self.prefix.load(reader).field("prefix")?;
         }
 
-        if name.is_empty() {
-            if let Some(item) = ItemMeta::get_by_name(item_meta, name) {
-                self.id = item.id;
-            }
-
-            if self.stack == 0 {
-                self.stack = 1;
-            }
+        if self.id != 0 && self.stack == 0 {
+            self.stack = 1
         }
 
         Ok(())
     }
 
+    /// Loads an item from a player slot, which is saved by name before version 38.
+    pub fn load_versioned(
+        &mut self,
+        reader: &mut dyn Read,
+        item_meta: &MetaRegistry<ItemMeta>,
+        version: i32,
+        stack: bool,
+        prefix: bool,
+        favourited: bool,
+    ) -> anyhow::Result<()> {
+        if version >= 38 {
+            self.load(reader, item_meta, true, false, stack, prefix, favourited)
+        } else {
+            self.load_from_legacy_name(reader, item_meta, version, stack, prefix)
+        }
+    }
+
     pub fn skip(
         reader: &mut dyn Read,
         id: bool,
@@ -200,12 +144,19 @@ impl Item {
         Ok(())
     }
 
-    pub fn skip_legacy_name(reader: &mut dyn Read, stack: bool) -> anyhow::Result<()> {
+    pub fn skip_legacy_name(
+        reader: &mut dyn Read,
+        stack: bool,
+        prefix: bool,
+    ) -> anyhow::Result<()> {
         let _ = reader.read_lpstring()?;
 
         if stack {
             let _ = reader.read_i32::<LE>()?;
         }
+        if prefix {
+            Prefix::skip(reader)?;
+        }
 
         Ok(())
     }
@@ -213,7 +164,7 @@ impl Item {
     pub fn save(
         &self,
         writer: &mut dyn Write,
-        item_meta: &[ItemMeta],
+        item_meta: &MetaRegistry<ItemMeta>,
         id: bool,
         internal_name: bool,
         stack: bool,
@@ -228,7 +179,7 @@ impl Item {
             writer.write_i32::<LE>(self.id)?;
         }
         if internal_name {
-            if let Some(item) = ItemMeta::get(item_meta, self.id) {
+            if let Some(item) = item_meta.get(self.id) {
                 writer.write_lpstring(&item.internal_name)?;
             } else {
                 writer.write_lpstring("")?;
@@ -250,21 +201,40 @@ impl Item {
     pub fn save_legacy_name(
         &self,
         writer: &mut dyn Write,
-        item_meta: &[ItemMeta],
+        item_meta: &MetaRegistry<ItemMeta>,
         version: i32,
         stack: bool,
+        prefix: bool,
     ) -> anyhow::Result<()> {
-        if let Some(item) = ItemMeta::get(item_meta, self.id) {
-            let name = Self::reverse_legacy_lookup(version, &item.name);
-            writer.write_lpstring(name)?;
-        } else {
-            writer.write_lpstring("")?;
+        match item_meta.get(self.id).filter(|_| self.id != 0) {
+            Some(item) => writer.write_lpstring(&item.legacy_name(version))?,
+            None => writer.write_lpstring("")?,
         }
 
         if stack {
             writer.write_i32::<LE>(self.stack)?;
         }
+        if prefix {
+            self.prefix.save(writer)?;
+        }
 
         Ok(())
     }
+
+    /// Saves an item to a player slot, by name if the version is older than 38.
+    pub fn save_versioned(
+        &self,
+        writer: &mut dyn Write,
+        item_meta: &MetaRegistry<ItemMeta>,
+        version: i32,
+        stack: bool,
+        prefix: bool,
+        favourited: bool,
+    ) -> anyhow::Result<()> {
+        if version >= 38 {
+            self.save(writer, item_meta, true, false, stack, prefix, favourited)
+        } else {
+            self.save_legacy_name(writer, item_meta, version, stack, prefix)
+        }
+    }
 }
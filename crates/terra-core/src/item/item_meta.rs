@@ -1,4 +1,7 @@
-use crate::{meta::Meta, ItemRarity, SharedString};
+use crate::{
+    meta::{Meta, MetaRegistry},
+    ItemRarity, PrefixMeta, SharedString,
+};
 
 #[repr(u8)]
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -64,6 +67,23 @@ pub struct ItemMeta {
     pub is_consumable: Option<bool>,
     pub is_quest_item: Option<bool>,
     pub is_expert: Option<bool>,
+    /// Melee weapons which attack with a projectile instead of being swung, like yoyos,
+    /// spears, flails and boomerangs.
+    pub is_projectile_melee: Option<bool>,
+    /// Names the item was saved under before it was renamed, see `LegacyName`.
+    pub legacy_names: Option<Vec<LegacyName>>,
+}
+
+/// Before version 38, players saved items by their English name rather than their id.
+///
+/// Items which have been renamed since are saved under their old name by versions up to
+/// and including `until`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct LegacyName {
+    pub name: SharedString,
+    pub until: i32,
 }
 
 impl Meta for ItemMeta {
@@ -80,4 +100,96 @@ impl Meta for ItemMeta {
     fn internal_name(&self) -> SharedString {
         self.internal_name.clone()
     }
+
+    fn legacy_names(&self) -> Vec<SharedString> {
+        self.legacy_names
+            .iter()
+            .flatten()
+            .map(|l| l.name.clone())
+            .collect()
+    }
+}
+
+impl ItemMeta {
+    /// Weapons, tools and accessories can be reforged, but not weapons that are consumed on
+    /// use. Accessories always can, as some (like music boxes) are also placeable.
+    pub fn can_have_prefix(&self) -> bool {
+        match self.item_type {
+            Some(ItemType::Accessory) => true,
+            Some(ItemType::Melee | ItemType::Ranged | ItemType::Magic | ItemType::Summon) => {
+                !self.is_consumable.is_some_and(|c| c)
+            }
+            _ => false,
+        }
+    }
+
+    /// How many of the item fit in one slot.
+    ///
+    /// The metadata gives almost every item a `max_stack` of 9999, so anything that can be
+    /// reforged, and armor and vanity, is taken not to stack.
+    pub fn effective_max_stack(&self) -> i32 {
+        let unstackable = self.can_have_prefix()
+            || matches!(
+                self.item_type,
+                Some(
+                    ItemType::HeadArmor
+                        | ItemType::BodyArmor
+                        | ItemType::LegArmor
+                        | ItemType::Vanity
+                )
+            );
+
+        if unstackable {
+            1
+        } else {
+            self.max_stack
+        }
+    }
+
+    /// Whether the game lets the item into an ammo slot. Besides ammo, that's bait, wire,
+    /// actuators, paint and coatings.
+    pub fn fits_ammo_slot(&self) -> bool {
+        self.item_type == Some(ItemType::Ammo)
+            || self.fishing_bait.is_some_and(|b| b > 0)
+            // Wire, Actuator, paints, Illuminant Coating and Echo Coating
+            || matches!(self.id, 530 | 849 | 1073..=1099 | 1966..=1968 | 4668 | 5344)
+    }
+
+    /// The prefixes the item can be reforged with, in id order.
+    pub fn valid_prefixes<'a>(
+        &'a self,
+        prefixes: &'a MetaRegistry<PrefixMeta>,
+    ) -> impl Iterator<Item = &'a PrefixMeta> {
+        prefixes.iter().filter(|p| p.is_valid_for(self))
+    }
+
+    /// The name a player of the given version saves this item under.
+    pub fn legacy_name(&self, version: i32) -> SharedString {
+        self.legacy_names
+            .iter()
+            .flatten()
+            .filter(|l| version <= l.until)
+            .min_by_key(|l| l.until)
+            .map(|l| l.name.clone())
+            .unwrap_or_else(|| self.name.clone())
+    }
+
+    /// Finds the item a player of the given version saved under `name`.
+    pub fn get_by_legacy_name<'a>(
+        meta: &'a MetaRegistry<ItemMeta>,
+        version: i32,
+        name: &str,
+    ) -> Option<&'a ItemMeta> {
+        meta.get_by_legacy_name(name)
+            .find(|m| {
+                m.legacy_names
+                    .iter()
+                    .flatten()
+                    .any(|l| version <= l.until && l.name == *name)
+            })
+            .or_else(|| {
+                meta.get_by_name(name)
+                    .filter(|m| m.legacy_name(version) == *name)
+            })
+    }
 }
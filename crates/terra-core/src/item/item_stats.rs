@@ -0,0 +1,121 @@
+use crate::{ItemMeta, ItemRarity, MetaBundle, PrefixCategory, PrefixMeta};
+
+use super::Item;
+
+/// An item's stats with its prefix applied, worked out the way the game does in `Item.Prefix`.
+///
+/// Accessory prefixes don't change the item itself, their bonuses are granted to the player
+/// while it's equipped, so only the value and rarity of an accessory change.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ItemStats {
+    pub damage: Option<i32>,
+    pub use_time: Option<i32>,
+    pub crit_chance: Option<i32>,
+    pub knockback: Option<f32>,
+    pub mana_cost: Option<i32>,
+    /// Multiplier on the item's size, for melee weapons.
+    pub scale: f32,
+    /// Multiplier on the speed of the projectiles the item shoots.
+    pub shoot_speed: f32,
+    pub rarity: ItemRarity,
+    /// The item's value in copper coins.
+    pub value: i32,
+}
+
+impl ItemStats {
+    pub fn new(item: &ItemMeta, prefix: Option<&PrefixMeta>) -> Self {
+        let mut stats = Self {
+            damage: item.damage,
+            use_time: item.use_time,
+            crit_chance: item.crit_chance,
+            knockback: item.knockback,
+            mana_cost: item.mana_cost,
+            scale: 1.,
+            shoot_speed: 1.,
+            rarity: item.rarity,
+            value: item.value,
+        };
+
+        let Some(prefix) = prefix.filter(|p| p.id != 0) else {
+            return stats;
+        };
+
+        let value_multiplier = if prefix.category == Some(PrefixCategory::Accessory) {
+            accessory_value_multiplier(prefix.id)
+        } else {
+            let damage = prefix.damage.unwrap_or(1.);
+            let use_time = prefix.use_time.unwrap_or(1.);
+            let knockback = prefix.knockback.unwrap_or(1.);
+            let mana_cost = prefix.mana_cost.unwrap_or(1.);
+            let crit = prefix.crit.unwrap_or(0);
+
+            stats.damage = stats.damage.map(|d| round(d, damage));
+            stats.use_time = stats.use_time.map(|t| round(t, use_time));
+            stats.mana_cost = stats.mana_cost.map(|m| round(m, mana_cost));
+            stats.knockback = stats.knockback.map(|k| k * knockback);
+            if crit != 0 {
+                stats.crit_chance = Some(stats.crit_chance.unwrap_or(0) + crit);
+            }
+            stats.scale = prefix.size.unwrap_or(1.);
+            stats.shoot_speed = prefix.velocity.unwrap_or(1.);
+
+            damage
+                * (2. - use_time)
+                * (2. - mana_cost)
+                * stats.scale
+                * knockback
+                * stats.shoot_speed
+                * (1. + crit as f32 * 0.02)
+        };
+
+        stats.rarity = shift_rarity(stats.rarity, value_multiplier);
+        stats.value = (stats.value as f32 * value_multiplier * value_multiplier) as i32;
+
+        stats
+    }
+}
+
+impl Item {
+    /// The item's stats with its prefix applied, or `None` if the item is unknown.
+    pub fn stats(&self, meta: &MetaBundle) -> Option<ItemStats> {
+        let item = meta.items.get(self.id)?;
+        Some(ItemStats::new(item, meta.prefixes.get(self.prefix.id)))
+    }
+}
+
+/// The game rounds with `Math.Round`, which rounds halves to even.
+fn round(stat: i32, multiplier: f32) -> i32 {
+    (stat as f32 * multiplier).round_ties_even() as i32
+}
+
+/// Accessory prefixes come in four tiers, each worth more than the last.
+fn accessory_value_multiplier(id: u8) -> f32 {
+    match id {
+        62 | 69 | 73 | 77 => 1.05,
+        63 | 70 | 74 | 78 | 67 => 1.1,
+        64 | 71 | 75 | 79 | 66 => 1.15,
+        65 | 72 | 76 | 80 | 68 => 1.2,
+        _ => 1.,
+    }
+}
+
+fn shift_rarity(rarity: ItemRarity, value_multiplier: f32) -> ItemRarity {
+    // Quest, expert and master items keep their special rarity
+    if !(ItemRarity::Gray..=ItemRarity::Purple).contains(&rarity) {
+        return rarity;
+    }
+
+    let shift = if value_multiplier >= 1.2 {
+        2
+    } else if value_multiplier >= 1.05 {
+        1
+    } else if value_multiplier <= 0.8 {
+        -2
+    } else if value_multiplier <= 0.95 {
+        -1
+    } else {
+        0
+    };
+
+    ItemRarity::from((i32::from(rarity) + shift).clamp(-1, 11))
+}
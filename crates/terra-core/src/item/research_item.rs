@@ -4,6 +4,7 @@ use byteorder::{ReadBytesExt, WriteBytesExt, LE};
 
 use crate::{
     ext::{TerraReadExt, TerraWriteExt},
+    parse::FieldContext,
     SharedString,
 };
 
@@ -17,8 +18,8 @@ pub struct ResearchItem {
 
 impl ResearchItem {
     pub fn load(&mut self, reader: &mut dyn Read) -> anyhow::Result<()> {
-        self.internal_name = SharedString::from(reader.read_lpstring()?);
-        self.stack = reader.read_i32::<LE>()?;
+        self.internal_name = SharedString::from(reader.read_lpstring().field("internal_name")?);
+        self.stack = reader.read_i32::<LE>().field("stack")?;
 
         Ok(())
     }
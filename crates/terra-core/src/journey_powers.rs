@@ -4,34 +4,73 @@ use byteorder::{ReadBytesExt, WriteBytesExt, LE};
 
 use crate::{
     ext::{TerraReadExt, TerraWriteExt},
+    parse::FieldContext,
     Difficulty,
 };
 
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
-#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
-pub struct JourneyPowers {
-    pub godmode: bool,
-    pub far_placement: bool,
-    pub spawnrate: f32,
+#[derive(thiserror::Error, Debug)]
+pub enum JourneyPowerError {
+    #[error("Journey power {id} can't be saved in a player as {value:?}, the game would misread the rest of the file.")]
+    InvalidValue { id: u16, value: JourneyPowerValue },
+    #[error(
+        "An unrecognised journey power holds the rest of the player, so it has to be the last one."
+    )]
+    RawNotLast,
 }
 
+/// Every creative power the game registers, in its registration order.
+///
+/// Only Godmode, FarPlacement and Spawnrate are saved with the player. The rest belong to the
+/// world, and the game reads nothing but their id if they show up in a player.
 #[repr(u16)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serialize", derive(serde_repr::Serialize_repr))]
 #[cfg_attr(feature = "deserialize", derive(serde_repr::Deserialize_repr))]
 pub enum JourneyPowerId {
+    FreezeTime = 0,
+    StartDay = 1,
+    StartNoon = 2,
+    StartNight = 3,
+    StartMidnight = 4,
     Godmode = 5,
+    WindStrength = 6,
+    RainStrength = 7,
+    TimeRate = 8,
+    FreezeRain = 9,
+    FreezeWind = 10,
     FarPlacement = 11,
+    Difficulty = 12,
+    FreezeBiomeSpread = 13,
     Spawnrate = 14,
     Unknown = u16::MAX,
 }
 
+/// The kind of value a power carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JourneyPowerKind {
+    /// A one-off action (e.g. setting the time to dawn), which has no value.
+    Button,
+    Toggle,
+    Slider,
+}
+
 impl From<u16> for JourneyPowerId {
     fn from(value: u16) -> Self {
         match value {
+            0 => Self::FreezeTime,
+            1 => Self::StartDay,
+            2 => Self::StartNoon,
+            3 => Self::StartNight,
+            4 => Self::StartMidnight,
             5 => Self::Godmode,
+            6 => Self::WindStrength,
+            7 => Self::RainStrength,
+            8 => Self::TimeRate,
+            9 => Self::FreezeRain,
+            10 => Self::FreezeWind,
             11 => Self::FarPlacement,
+            12 => Self::Difficulty,
+            13 => Self::FreezeBiomeSpread,
             14 => Self::Spawnrate,
             _ => Self::Unknown,
         }
@@ -40,36 +79,249 @@ impl From<u16> for JourneyPowerId {
 
 impl From<JourneyPowerId> for u16 {
     fn from(value: JourneyPowerId) -> Self {
+        value as u16
+    }
+}
+
+impl JourneyPowerId {
+    /// `None` for `Unknown`, whose payload can't be interpreted.
+    pub fn kind(&self) -> Option<JourneyPowerKind> {
+        match self {
+            Self::StartDay | Self::StartNoon | Self::StartNight | Self::StartMidnight => {
+                Some(JourneyPowerKind::Button)
+            }
+            Self::FreezeTime
+            | Self::Godmode
+            | Self::FreezeRain
+            | Self::FreezeWind
+            | Self::FarPlacement
+            | Self::FreezeBiomeSpread => Some(JourneyPowerKind::Toggle),
+            Self::WindStrength
+            | Self::RainStrength
+            | Self::TimeRate
+            | Self::Difficulty
+            | Self::Spawnrate => Some(JourneyPowerKind::Slider),
+            Self::Unknown => None,
+        }
+    }
+
+    /// Whether the game saves the power's value with the player.
+    pub fn is_per_player(&self) -> bool {
+        matches!(self, Self::Godmode | Self::FarPlacement | Self::Spawnrate)
+    }
+
+    /// Whether the game reads the value back from a player as it was written. Per-player
+    /// powers need a value of their kind, and any other known power no value at all.
+    pub fn accepts(&self, value: &JourneyPowerValue) -> bool {
         match value {
-            JourneyPowerId::Godmode => 5,
-            JourneyPowerId::FarPlacement => 11,
-            JourneyPowerId::Spawnrate => 14,
-            JourneyPowerId::Unknown => u16::MAX,
+            JourneyPowerValue::None => !self.is_per_player() && *self != Self::Unknown,
+            JourneyPowerValue::Toggle(_) => {
+                self.is_per_player() && self.kind() == Some(JourneyPowerKind::Toggle)
+            }
+            JourneyPowerValue::Slider(_) => {
+                self.is_per_player() && self.kind() == Some(JourneyPowerKind::Slider)
+            }
+            JourneyPowerValue::Raw(_) => false,
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum JourneyPowerValue {
+    None,
+    Toggle(bool),
+    /// The slider's position, from 0 to 1.
+    Slider(f32),
+    /// Everything after the id of a power terra-rs doesn't recognise. Its payload's size isn't
+    /// saved, so this is the rest of the player, which is written back as-is in place of the
+    /// fields that would follow.
+    Raw(Vec<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct JourneyPower {
+    /// Kept as a number so unknown powers keep their id.
+    pub id: u16,
+    pub value: JourneyPowerValue,
+}
+
+impl JourneyPower {
+    pub fn new(id: JourneyPowerId, value: JourneyPowerValue) -> Self {
+        Self {
+            id: u16::from(id),
+            value,
+        }
+    }
+
+    pub fn power_id(&self) -> JourneyPowerId {
+        JourneyPowerId::from(self.id)
+    }
+
+    pub fn load(reader: &mut dyn Read) -> anyhow::Result<Self> {
+        let id = reader.read_u16::<LE>().field("id")?;
+        let power_id = JourneyPowerId::from(id);
+
+        let value = match power_id.kind() {
+            // The game stops reading powers at an id it doesn't know, and as the payload's size
+            // isn't saved, nothing after it can be read either
+            None => {
+                let mut rest = Vec::new();
+                reader.read_to_end(&mut rest).field("value")?;
+                JourneyPowerValue::Raw(rest)
+            }
+            Some(_) if !power_id.is_per_player() => JourneyPowerValue::None,
+            Some(JourneyPowerKind::Toggle) => {
+                JourneyPowerValue::Toggle(reader.read_bool().field("value")?)
+            }
+            Some(JourneyPowerKind::Slider) => {
+                JourneyPowerValue::Slider(reader.read_f32::<LE>().field("value")?)
+            }
+            Some(JourneyPowerKind::Button) => JourneyPowerValue::None,
+        };
+
+        Ok(Self { id, value })
+    }
+
+    pub fn save(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        writer.write_u16::<LE>(self.id)?;
+
+        match &self.value {
+            JourneyPowerValue::None => {}
+            JourneyPowerValue::Toggle(value) => writer.write_bool(*value)?,
+            JourneyPowerValue::Slider(value) => writer.write_f32::<LE>(*value)?,
+            JourneyPowerValue::Raw(payload) => writer.write_all(payload)?,
+        }
+
+        Ok(())
+    }
+}
+
+/// The creative powers saved in a player, in the order they were loaded.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct JourneyPowers {
+    pub powers: Vec<JourneyPower>,
+}
+
 impl Default for JourneyPowers {
     fn default() -> Self {
         Self {
-            godmode: false,
-            far_placement: true,
-            spawnrate: 0.5,
+            powers: vec![
+                JourneyPower::new(JourneyPowerId::Godmode, JourneyPowerValue::Toggle(false)),
+                JourneyPower::new(
+                    JourneyPowerId::FarPlacement,
+                    JourneyPowerValue::Toggle(true),
+                ),
+                JourneyPower::new(JourneyPowerId::Spawnrate, JourneyPowerValue::Slider(0.5)),
+            ],
         }
     }
 }
 
 impl JourneyPowers {
+    pub fn get(&self, id: JourneyPowerId) -> Option<&JourneyPowerValue> {
+        let id = u16::from(id);
+        self.powers.iter().find(|p| p.id == id).map(|p| &p.value)
+    }
+
+    /// Whether the last power is an unrecognised one, holding the rest of the player.
+    pub fn has_raw_tail(&self) -> bool {
+        self.powers
+            .last()
+            .is_some_and(|p| matches!(p.value, JourneyPowerValue::Raw(_)))
+    }
+
+    /// Checks that the game would read the powers back as they are.
+    pub fn check(&self) -> Result<(), JourneyPowerError> {
+        for (i, power) in self.powers.iter().enumerate() {
+            let valid = match &power.value {
+                JourneyPowerValue::Raw(_) if power.power_id() == JourneyPowerId::Unknown => {
+                    if i + 1 != self.powers.len() {
+                        return Err(JourneyPowerError::RawNotLast);
+                    }
+                    true
+                }
+                value => power.power_id().accepts(value),
+            };
+
+            if !valid {
+                return Err(JourneyPowerError::InvalidValue {
+                    id: power.id,
+                    value: power.value.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the power's value, or adds it before any unrecognised power.
+    ///
+    /// Fails if the game wouldn't read the value back, like a toggle for FreezeTime, which
+    /// belongs to the world.
+    pub fn set(
+        &mut self,
+        id: JourneyPowerId,
+        value: JourneyPowerValue,
+    ) -> Result<(), JourneyPowerError> {
+        let raw_id = u16::from(id);
+        if !id.accepts(&value) {
+            return Err(JourneyPowerError::InvalidValue { id: raw_id, value });
+        }
+
+        match self.powers.iter_mut().find(|p| p.id == raw_id) {
+            Some(power) => power.value = value,
+            None => {
+                let index = self.powers.len() - usize::from(self.has_raw_tail());
+                self.powers.insert(index, JourneyPower::new(id, value));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: JourneyPowerId) -> Option<JourneyPowerValue> {
+        let id = u16::from(id);
+        let index = self.powers.iter().position(|p| p.id == id)?;
+        Some(self.powers.remove(index).value)
+    }
+
+    pub fn toggle(&self, id: JourneyPowerId) -> Option<bool> {
+        match self.get(id) {
+            Some(JourneyPowerValue::Toggle(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn slider(&self, id: JourneyPowerId) -> Option<f32> {
+        match self.get(id) {
+            Some(JourneyPowerValue::Slider(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn set_toggle(&mut self, id: JourneyPowerId, value: bool) -> Result<(), JourneyPowerError> {
+        self.set(id, JourneyPowerValue::Toggle(value))
+    }
+
+    pub fn set_slider(&mut self, id: JourneyPowerId, value: f32) -> Result<(), JourneyPowerError> {
+        self.set(id, JourneyPowerValue::Slider(value.clamp(0., 1.)))
+    }
+
     pub fn load(&mut self, reader: &mut dyn Read) -> anyhow::Result<()> {
+        self.powers.clear();
+
         while reader.read_bool()? {
-            let id = JourneyPowerId::from(reader.read_u16::<LE>()?);
-            match id {
-                JourneyPowerId::Godmode => self.godmode = reader.read_bool()?,
-                JourneyPowerId::FarPlacement => self.far_placement = reader.read_bool()?,
-                JourneyPowerId::Spawnrate => self.spawnrate = reader.read_f32::<LE>()?,
-                _ => {
-                    eprintln!("Unknown JourneyPowerID: {:?}", id);
-                }
+            let power = JourneyPower::load(reader).field_index("powers", self.powers.len())?;
+            self.powers.push(power);
+
+            if self.has_raw_tail() {
+                break;
             }
         }
 
@@ -77,32 +329,30 @@ impl JourneyPowers {
     }
 
     pub fn save(&self, writer: &mut dyn Write, difficulty: &Difficulty) -> anyhow::Result<()> {
+        self.check()?;
+
         if difficulty == &Difficulty::Journey {
-            writer.write_bool(true)?;
-            writer.write_u16::<LE>(u16::from(JourneyPowerId::Godmode))?;
-            writer.write_bool(self.godmode)?;
-            writer.write_bool(true)?;
-            writer.write_u16::<LE>(u16::from(JourneyPowerId::FarPlacement))?;
-            writer.write_bool(self.far_placement)?;
-            writer.write_bool(true)?;
-            writer.write_u16::<LE>(u16::from(JourneyPowerId::Spawnrate))?;
-            writer.write_f32::<LE>(self.spawnrate)?;
+            for power in self.powers.iter() {
+                writer.write_bool(true)?;
+                power.save(writer)?;
+            }
         } else {
             // Terrasavr just writes a 0x00 in this case, but this is how Terraria itself does it
-            let default = Self::default();
-
-            writer.write_bool(true)?;
-            writer.write_u16::<LE>(u16::from(JourneyPowerId::Godmode))?;
-            writer.write_bool(default.godmode)?;
-            writer.write_bool(true)?;
-            writer.write_u16::<LE>(u16::from(JourneyPowerId::FarPlacement))?;
-            writer.write_bool(default.far_placement)?;
-            writer.write_bool(true)?;
-            writer.write_u16::<LE>(u16::from(JourneyPowerId::Spawnrate))?;
-            writer.write_f32::<LE>(default.spawnrate)?;
+            let unknown = self
+                .powers
+                .iter()
+                .filter(|p| p.power_id() == JourneyPowerId::Unknown);
+
+            for power in Self::default().powers.iter().chain(unknown) {
+                writer.write_bool(true)?;
+                power.save(writer)?;
+            }
         }
 
-        writer.write_bool(false)?;
+        // An unrecognised power's payload already holds the end of the list
+        if !self.has_raw_tail() {
+            writer.write_bool(false)?;
+        }
 
         Ok(())
     }
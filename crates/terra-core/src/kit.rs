@@ -0,0 +1,319 @@
+use std::{collections::HashSet, fmt::Display};
+
+use crate::{
+    ContainerKind, Item, Player, SlotRef, ACCESSORY_COUNT, AMMO_COUNT, ARMOR_COUNT, COINS_COUNT,
+    EQUIPMENT_COUNT, INVENTORY_COUNT, LOADOUT_COUNT,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum KitError {
+    #[error("The kit's slot {0} doesn't exist.")]
+    InvalidSlot(KitSlot),
+    #[error("The kit fills {0} more than once.")]
+    DuplicateSlot(KitSlot),
+}
+
+/// A slot a kit can fill. Loadout slots are by loadout, then slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+#[cfg_attr(
+    any(feature = "serialize", feature = "deserialize"),
+    serde(rename_all = "snake_case")
+)]
+pub enum KitSlot {
+    Armor(usize, usize),
+    VanityArmor(usize, usize),
+    ArmorDye(usize, usize),
+    Accessory(usize, usize),
+    VanityAccessory(usize, usize),
+    AccessoryDye(usize, usize),
+    Equipment(usize),
+    EquipmentDye(usize),
+    Inventory(usize),
+    Coin(usize),
+    Ammo(usize),
+}
+
+impl KitSlot {
+    /// Every armor, accessory, vanity and dye slot of a loadout.
+    pub fn loadout(loadout: usize) -> impl Iterator<Item = KitSlot> {
+        let armor = (0..ARMOR_COUNT).flat_map(move |i| {
+            [
+                Self::Armor(loadout, i),
+                Self::VanityArmor(loadout, i),
+                Self::ArmorDye(loadout, i),
+            ]
+        });
+        let accessories = (0..ACCESSORY_COUNT).flat_map(move |i| {
+            [
+                Self::Accessory(loadout, i),
+                Self::VanityAccessory(loadout, i),
+                Self::AccessoryDye(loadout, i),
+            ]
+        });
+        armor.chain(accessories)
+    }
+
+    fn is_valid(&self) -> bool {
+        match *self {
+            Self::Armor(loadout, i)
+            | Self::VanityArmor(loadout, i)
+            | Self::ArmorDye(loadout, i) => loadout < LOADOUT_COUNT && i < ARMOR_COUNT,
+            Self::Accessory(loadout, i)
+            | Self::VanityAccessory(loadout, i)
+            | Self::AccessoryDye(loadout, i) => loadout < LOADOUT_COUNT && i < ACCESSORY_COUNT,
+            Self::Equipment(i) | Self::EquipmentDye(i) => i < EQUIPMENT_COUNT,
+            Self::Inventory(i) => i < INVENTORY_COUNT,
+            Self::Coin(i) => i < COINS_COUNT,
+            Self::Ammo(i) => i < AMMO_COUNT,
+        }
+    }
+
+    /// The slot the kit fills.
+    pub fn slot_ref(&self) -> SlotRef {
+        let (container, index) = match *self {
+            Self::Armor(loadout, i) => (ContainerKind::Armor(loadout), i),
+            Self::VanityArmor(loadout, i) => (ContainerKind::VanityArmor(loadout), i),
+            Self::ArmorDye(loadout, i) => (ContainerKind::ArmorDyes(loadout), i),
+            Self::Accessory(loadout, i) => (ContainerKind::Accessories(loadout), i),
+            Self::VanityAccessory(loadout, i) => (ContainerKind::VanityAccessories(loadout), i),
+            Self::AccessoryDye(loadout, i) => (ContainerKind::AccessoryDyes(loadout), i),
+            Self::Equipment(i) => (ContainerKind::Equipment, i),
+            Self::EquipmentDye(i) => (ContainerKind::EquipmentDyes, i),
+            Self::Inventory(i) => (ContainerKind::Inventory, i),
+            Self::Coin(i) => (ContainerKind::Coins, i),
+            Self::Ammo(i) => (ContainerKind::Ammo, i),
+        };
+        SlotRef::new(container, index)
+    }
+
+    /// Panics if the slot isn't valid.
+    fn item<'a>(&self, player: &'a Player) -> &'a Item {
+        self.slot_ref().get(player).expect("kit slots are checked")
+    }
+
+    fn item_mut<'a>(&self, player: &'a mut Player) -> &'a mut Item {
+        self.slot_ref()
+            .get_mut(player)
+            .expect("kit slots are checked")
+    }
+}
+
+impl Display for KitSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Armor(loadout, i) => write!(f, "loadouts[{loadout}].armor[{i}]"),
+            Self::VanityArmor(loadout, i) => write!(f, "loadouts[{loadout}].vanity_armor[{i}]"),
+            Self::ArmorDye(loadout, i) => write!(f, "loadouts[{loadout}].armor_dyes[{i}]"),
+            Self::Accessory(loadout, i) => write!(f, "loadouts[{loadout}].accessories[{i}]"),
+            Self::VanityAccessory(loadout, i) => {
+                write!(f, "loadouts[{loadout}].vanity_accessories[{i}]")
+            }
+            Self::AccessoryDye(loadout, i) => {
+                write!(f, "loadouts[{loadout}].accessory_dyes[{i}]")
+            }
+            Self::Equipment(i) => write!(f, "equipment[{i}]"),
+            Self::EquipmentDye(i) => write!(f, "equipment_dyes[{i}]"),
+            Self::Inventory(i) => write!(f, "inventory[{i}]"),
+            Self::Coin(i) => write!(f, "coins[{i}]"),
+            Self::Ammo(i) => write!(f, "ammo[{i}]"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct KitEntry {
+    pub slot: KitSlot,
+    pub item: Item,
+}
+
+/// A set of items to put in specific slots, such as a class's armor, accessories, ammo
+/// and potions, which can be applied to any player.
+#[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct Kit {
+    pub name: String,
+    pub entries: Vec<KitEntry>,
+}
+
+impl Kit {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Copies the player's items in the given slots into a kit, leaving out empty slots.
+    pub fn capture(
+        name: impl Into<String>,
+        player: &Player,
+        slots: impl IntoIterator<Item = KitSlot>,
+    ) -> Result<Self, KitError> {
+        let mut kit = Self::new(name);
+
+        for slot in slots {
+            if !slot.is_valid() {
+                return Err(KitError::InvalidSlot(slot));
+            }
+
+            let item = slot.item(player);
+            if item.id != 0 {
+                kit.entries.push(KitEntry {
+                    slot,
+                    item: item.clone(),
+                });
+            }
+        }
+
+        Ok(kit)
+    }
+
+    fn check(&self) -> Result<(), KitError> {
+        let mut slots = HashSet::new();
+        for entry in &self.entries {
+            if !entry.slot.is_valid() {
+                return Err(KitError::InvalidSlot(entry.slot));
+            }
+            if !slots.insert(entry.slot) {
+                return Err(KitError::DuplicateSlot(entry.slot));
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "deserialize")]
+    pub fn load(filepath: &std::path::Path) -> anyhow::Result<Self> {
+        let file = std::fs::File::open(filepath)?;
+        let kit: Self = serde_json::from_reader(std::io::BufReader::new(file))?;
+        kit.check()?;
+        Ok(kit)
+    }
+
+    #[cfg(feature = "serialize")]
+    pub fn save(&self, filepath: &std::path::Path) -> anyhow::Result<()> {
+        let file = std::fs::File::create(filepath)?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), self)?;
+        Ok(())
+    }
+}
+
+/// What `Player::apply_kit` does when a kit's slot already holds a different item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Replace the player's item with the kit's.
+    Overwrite,
+    /// Keep the player's item, and leave out the kit's.
+    Skip,
+    /// Move the player's item to an empty inventory slot, or skip the kit's item if there
+    /// isn't one.
+    MoveToInventory,
+}
+
+/// A kit's slot that already held a different item, and what happened to that item.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum KitConflict {
+    Overwritten {
+        slot: KitSlot,
+        id: i32,
+    },
+    Skipped {
+        slot: KitSlot,
+        id: i32,
+    },
+    Moved {
+        slot: KitSlot,
+        id: i32,
+        to: usize,
+    },
+    /// The item should have been moved, but the inventory was full.
+    NoRoom {
+        slot: KitSlot,
+        id: i32,
+    },
+}
+
+impl Display for KitConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Overwritten { slot, id } => write!(f, "Replaced item {id} in {slot}"),
+            Self::Skipped { slot, id } => {
+                write!(f, "Kept item {id} in {slot}, instead of the kit's")
+            }
+            Self::Moved { slot, id, to } => {
+                write!(f, "Moved item {id} from {slot} to inventory[{to}]")
+            }
+            Self::NoRoom { slot, id } => write!(
+                f,
+                "Kept item {id} in {slot}, as there was no room in the inventory to move it"
+            ),
+        }
+    }
+}
+
+impl Player {
+    /// Puts the kit's items in their slots, returning the slots which already held a
+    /// different item.
+    ///
+    /// Items are only moved into inventory slots the kit doesn't fill, and which the
+    /// player's version has.
+    pub fn apply_kit(
+        &mut self,
+        kit: &Kit,
+        policy: ConflictPolicy,
+    ) -> anyhow::Result<Vec<KitConflict>> {
+        kit.check()?;
+
+        let reserved: HashSet<usize> = kit
+            .entries
+            .iter()
+            .filter_map(|entry| match entry.slot {
+                KitSlot::Inventory(i) => Some(i),
+                _ => None,
+            })
+            .collect();
+        let inventory_count = if self.version >= 58 { 50 } else { 40 };
+
+        let mut conflicts = Vec::new();
+        for entry in &kit.entries {
+            let slot = entry.slot;
+            let existing = slot.item_mut(self);
+            let id = existing.id;
+
+            if id == 0 || id == entry.item.id {
+                *existing = entry.item.clone();
+                continue;
+            }
+
+            match policy {
+                ConflictPolicy::Overwrite => {
+                    *existing = entry.item.clone();
+                    conflicts.push(KitConflict::Overwritten { slot, id });
+                }
+                ConflictPolicy::Skip => conflicts.push(KitConflict::Skipped { slot, id }),
+                ConflictPolicy::MoveToInventory => {
+                    let to = self.inventory[..inventory_count]
+                        .iter()
+                        .enumerate()
+                        .position(|(i, item)| item.id == 0 && !reserved.contains(&i));
+                    let Some(to) = to else {
+                        conflicts.push(KitConflict::NoRoom { slot, id });
+                        continue;
+                    };
+
+                    let item = std::mem::replace(slot.item_mut(self), entry.item.clone());
+                    self.inventory[to] = item;
+                    conflicts.push(KitConflict::Moved { slot, id, to });
+                }
+            }
+        }
+
+        Ok(conflicts)
+    }
+}
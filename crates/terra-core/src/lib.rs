@@ -1,19 +1,34 @@
 mod aes;
 pub mod bool_byte;
 pub mod buff;
+pub mod diff;
 pub mod difficulty;
+pub mod downgrade;
 mod ext;
 pub mod file_type;
+#[cfg(all(feature = "serialize", feature = "deserialize"))]
+pub mod interchange;
+pub mod inventory;
 pub mod item;
 pub mod journey_powers;
+pub mod kit;
 pub mod loadout;
+pub mod map;
 pub mod meta;
+mod parse;
+#[cfg(all(feature = "serialize", feature = "deserialize"))]
+pub mod patch;
 pub mod player;
 pub mod prefix;
+pub mod sanitize;
 mod shared_string;
+pub mod slot;
 pub mod spawnpoint;
 pub mod team;
+pub mod tmod;
 pub mod utils;
+pub mod validate;
+pub mod world;
 
 pub const MAGIC_MASK: u64 = 0xFFFFFFFFFFFFFF;
 pub const MAGIC_NUMBER: u64 = 0x6369676F6C6572;
@@ -41,6 +56,7 @@ pub const AMMO_COUNT: usize = 4;
 pub const EQUIPMENT_COUNT: usize = 5;
 pub const BANK_COUNT: usize = 40;
 pub const BANK_STRIDE: usize = 10;
+pub const CHEST_ITEM_COUNT: usize = 40;
 pub const BUFF_COUNT: usize = 44;
 pub const BUFF_STRIDE: usize = 11;
 pub const SPAWNPOINT_LIMIT: usize = 200;
@@ -55,6 +71,12 @@ pub const TEMPORARY_SLOT_COUNT: usize = 4;
 pub const LOADOUT_COUNT: usize = 3;
 
 pub const MAX_RESPAWN_TIME: i32 = 60000;
+/// 400 from life crystals and 100 from life fruit
+pub const MAX_LIFE: i32 = 500;
+pub const MAX_MANA: i32 = 200;
+
+/// Copper, silver, gold and platinum coins
+pub const COIN_IDS: [i32; 4] = [71, 72, 73, 74];
 
 // Strange brew is strange
 pub const STRANGE_BREW_ID: i32 = 3001;
@@ -74,13 +96,35 @@ pub type Color = [u8; 3];
 
 pub use bool_byte::{BoolByte, BoolByteError};
 pub use buff::{Buff, BuffMeta, BuffType};
+pub use diff::{Change, ChangeValue};
 pub use difficulty::Difficulty;
+pub use downgrade::{DowngradeChange, DowngradeReport};
 pub use file_type::FileType;
-pub use item::{Item, ItemError, ItemMeta, ItemRarity, ItemType, ResearchItem};
-pub use journey_powers::{JourneyPowerId, JourneyPowers};
-pub use loadout::Loadout;
-pub use player::{Player, PlayerError};
-pub use prefix::{Prefix, PrefixMeta};
+#[cfg(all(feature = "serialize", feature = "deserialize"))]
+pub use interchange::{InterchangeError, PlayerDocument};
+pub use inventory::{Bank, InventoryError};
+pub use item::{
+    Item, ItemError, ItemMeta, ItemRarity, ItemStats, ItemType, LegacyName, ResearchItem,
+};
+pub use journey_powers::{
+    JourneyPower, JourneyPowerError, JourneyPowerId, JourneyPowerKind, JourneyPowerValue,
+    JourneyPowers,
+};
+pub use kit::{ConflictPolicy, Kit, KitConflict, KitEntry, KitError, KitSlot};
+pub use loadout::{Loadout, LoadoutError};
+pub use map::{Map, MapError, MapLayers, MapTile, MapTileKind};
+pub use meta::{MetaBundle, MetaRegistry};
+#[cfg(all(feature = "serialize", feature = "deserialize"))]
+pub use patch::{PatchError, PatchOperation, PlayerPatch};
+pub use player::{Player, PlayerError, PlayerFormat};
+pub use prefix::{Prefix, PrefixCategory, PrefixMeta};
+pub use sanitize::Repair;
 pub use shared_string::SharedString;
+pub use slot::{ContainerKind, FoundItem, SlotError, SlotRef};
 pub use spawnpoint::Spawnpoint;
 pub use team::Team;
+pub use tmod::{ModItem, ModPlayer, Tag, TagCompound, TagError};
+pub use validate::{Diagnostic, DiagnosticKind, Severity};
+pub use world::{
+    BrickStyle, Chest, LiquidType, Tile, TileGrid, World, WorldDifficulty, WorldError, WorldSize,
+};
@@ -4,9 +4,28 @@ use byteorder::{ReadBytesExt, WriteBytesExt};
 
 use crate::{
     ext::{TerraReadExt, TerraWriteExt},
-    utils, BoolByte, Item, ItemMeta, ACCESSORY_COUNT, ARMOR_COUNT, HIDDEN_VISUAL_COUNT,
+    meta::MetaRegistry,
+    parse::FieldContext,
+    utils, BoolByte, DowngradeChange, DowngradeReport, Item, ItemMeta, Player, ACCESSORY_COUNT,
+    ARMOR_COUNT, HIDDEN_VISUAL_COUNT, LOADOUT_COUNT,
 };
 
+#[derive(thiserror::Error, Debug)]
+pub enum LoadoutError {
+    #[error("Loadout {0} doesn't exist.")]
+    InvalidIndex(usize),
+    #[error("Version {0} players only have one loadout.")]
+    Unsupported(i32),
+    #[error(
+        "The loadout has items a version {version} player can't hold: {}",
+        .changes.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    UnsupportedByVersion {
+        version: i32,
+        changes: Vec<DowngradeChange>,
+    },
+}
+
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
@@ -42,56 +61,53 @@ impl Loadout {
     pub fn load(
         &mut self,
         reader: &mut dyn Read,
-        item_meta: &[ItemMeta],
+        item_meta: &MetaRegistry<ItemMeta>,
         version: i32,
         stack: bool,
         prefix: bool,
     ) -> anyhow::Result<()> {
         let accessory_count = if version >= 124 { 7 } else { 5 };
 
-        for armor in self.armor.iter_mut() {
-            if version >= 38 {
-                armor.load(reader, item_meta, true, false, stack, prefix, false)?;
-            } else {
-                armor.load_from_legacy_name(reader, item_meta, version, stack)?;
-            }
+        for (i, armor) in self.armor.iter_mut().enumerate() {
+            armor
+                .load_versioned(reader, item_meta, version, stack, prefix, false)
+                .field_index("armor", i)?;
         }
 
         for i in 0..accessory_count {
-            if version >= 38 {
-                self.accessories[i].load(reader, item_meta, true, false, stack, prefix, false)?;
-            } else {
-                self.accessories[i].load_from_legacy_name(reader, item_meta, version, stack)?;
-            }
+            self.accessories[i]
+                .load_versioned(reader, item_meta, version, stack, prefix, false)
+                .field_index("accessories", i)?;
         }
 
         if version >= 6 {
-            for vanity in self.vanity_armor.iter_mut() {
-                if version >= 38 {
-                    vanity.load(reader, item_meta, true, false, stack, prefix, false)?;
-                } else {
-                    vanity.load_from_legacy_name(reader, item_meta, version, stack)?;
-                }
+            for (i, vanity) in self.vanity_armor.iter_mut().enumerate() {
+                vanity
+                    .load_versioned(reader, item_meta, version, stack, prefix, false)
+                    .field_index("vanity_armor", i)?;
             }
         }
 
         if version >= 81 {
             for i in 0..accessory_count {
                 self.vanity_accessories[i]
-                    .load(reader, item_meta, true, false, stack, prefix, false)?;
+                    .load(reader, item_meta, true, false, stack, prefix, false)
+                    .field_index("vanity_accessories", i)?;
             }
         }
 
         if version >= 47 {
-            for dye in self.armor_dyes.iter_mut() {
-                dye.load(reader, item_meta, true, false, stack, prefix, false)?;
+            for (i, dye) in self.armor_dyes.iter_mut().enumerate() {
+                dye.load(reader, item_meta, true, false, stack, prefix, false)
+                    .field_index("armor_dyes", i)?;
             }
         }
 
         if version >= 81 {
             for i in 0..accessory_count {
                 self.accessory_dyes[i]
-                    .load(reader, item_meta, true, false, stack, prefix, false)?;
+                    .load(reader, item_meta, true, false, stack, prefix, false)
+                    .field_index("accessory_dyes", i)?;
             }
         }
 
@@ -105,14 +121,14 @@ impl Loadout {
         use_boolbyte: bool,
     ) -> anyhow::Result<()> {
         if use_boolbyte {
-            let mut bb = BoolByte::from(reader.read_u8()?);
+            let mut bb = BoolByte::from(reader.read_u8().field("hide_visual")?);
 
             for i in 0u8..8 {
                 self.hide_visual[i as usize] = bb.get(i)?;
             }
 
             if version >= 124 {
-                bb = BoolByte::from(reader.read_u8()?);
+                bb = BoolByte::from(reader.read_u8().field("hide_visual")?);
 
                 for i in 0u8..2 {
                     self.hide_visual[(i + 8) as usize] = bb.get(i)?;
@@ -120,8 +136,8 @@ impl Loadout {
             }
         } else {
             // We don't need to do version checking here since this only happens in 1.4.4+
-            for v in self.hide_visual.iter_mut() {
-                *v = reader.read_bool()?;
+            for (i, v) in self.hide_visual.iter_mut().enumerate() {
+                *v = reader.read_bool().field_index("hide_visual", i)?;
             }
         }
 
@@ -141,7 +157,7 @@ impl Loadout {
                 Item::skip(reader, true, false, stack, prefix, false)?;
             } else {
                 // This should technically be never used, but oh well
-                Item::skip_legacy_name(reader, stack)?;
+                Item::skip_legacy_name(reader, stack, prefix)?;
             }
         }
 
@@ -149,7 +165,7 @@ impl Loadout {
             if version >= 38 {
                 Item::skip(reader, true, false, stack, prefix, false)?;
             } else {
-                Item::skip_legacy_name(reader, stack)?;
+                Item::skip_legacy_name(reader, stack, prefix)?;
             }
         }
 
@@ -158,7 +174,7 @@ impl Loadout {
                 if version >= 38 {
                     Item::skip(reader, true, false, stack, prefix, false)?;
                 } else {
-                    Item::skip_legacy_name(reader, stack)?;
+                    Item::skip_legacy_name(reader, stack, prefix)?;
                 }
             }
         }
@@ -207,7 +223,7 @@ impl Loadout {
     pub fn save(
         &self,
         writer: &mut dyn Write,
-        item_meta: &[ItemMeta],
+        item_meta: &MetaRegistry<ItemMeta>,
         version: i32,
         stack: bool,
         prefix: bool,
@@ -215,28 +231,16 @@ impl Loadout {
         let accessory_count = if version >= 124 { 7 } else { 5 };
 
         for armor in self.armor.iter() {
-            if version >= 38 {
-                armor.save(writer, item_meta, true, false, stack, prefix, false)?;
-            } else {
-                armor.save_legacy_name(writer, item_meta, version, stack)?;
-            }
+            armor.save_versioned(writer, item_meta, version, stack, prefix, false)?;
         }
 
         for i in 0..accessory_count {
-            if version >= 38 {
-                self.accessories[i].save(writer, item_meta, true, false, stack, prefix, false)?;
-            } else {
-                self.accessories[i].save_legacy_name(writer, item_meta, version, stack)?;
-            }
+            self.accessories[i].save_versioned(writer, item_meta, version, stack, prefix, false)?;
         }
 
         if version >= 6 {
             for vanity in self.vanity_armor.iter() {
-                if version >= 38 {
-                    vanity.save(writer, item_meta, true, false, stack, prefix, false)?;
-                } else {
-                    vanity.save_legacy_name(writer, item_meta, version, stack)?;
-                }
+                vanity.save_versioned(writer, item_meta, version, stack, prefix, false)?;
             }
         }
 
@@ -305,4 +309,87 @@ impl Loadout {
             || utils::has_item(id, &self.armor_dyes)
             || utils::has_item(id, &self.accessory_dyes)
     }
+
+    #[cfg(feature = "deserialize")]
+    pub fn load_json(filepath: &std::path::Path) -> anyhow::Result<Self> {
+        let file = std::fs::File::open(filepath)?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
+
+    #[cfg(feature = "serialize")]
+    pub fn save_json(&self, filepath: &std::path::Path) -> anyhow::Result<()> {
+        let file = std::fs::File::create(filepath)?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), self)?;
+        Ok(())
+    }
+}
+
+impl Player {
+    fn check_loadout_index(&self, index: usize) -> Result<(), LoadoutError> {
+        if index >= LOADOUT_COUNT {
+            return Err(LoadoutError::InvalidIndex(index));
+        }
+        // Players before 1.4.4 only have the equipped loadout
+        if self.version < 262 && index != 0 {
+            return Err(LoadoutError::Unsupported(self.version));
+        }
+        Ok(())
+    }
+
+    /// Equips a loadout, as switching loadouts in game does.
+    ///
+    /// Each loadout keeps its own items, with `current_loadout_index` saying which one the
+    /// player is wearing, so nothing moves between loadouts.
+    pub fn set_active_loadout(&mut self, index: usize) -> Result<(), LoadoutError> {
+        self.check_loadout_index(index)?;
+        self.current_loadout_index = index as i32;
+        Ok(())
+    }
+
+    /// Replaces loadout `to` with a copy of loadout `from`, including its visibility.
+    pub fn copy_loadout(&mut self, from: usize, to: usize) -> Result<(), LoadoutError> {
+        self.check_loadout_index(from)?;
+        self.check_loadout_index(to)?;
+        self.loadouts[to] = self.loadouts[from].clone();
+        Ok(())
+    }
+
+    /// Swaps two loadouts. If either is equipped, the player keeps wearing the same items,
+    /// now in the other loadout.
+    pub fn swap_loadouts(&mut self, a: usize, b: usize) -> Result<(), LoadoutError> {
+        self.check_loadout_index(a)?;
+        self.check_loadout_index(b)?;
+        self.loadouts.swap(a, b);
+
+        let current = self.current_loadout_index as usize;
+        if current == a {
+            self.current_loadout_index = b as i32;
+        } else if current == b {
+            self.current_loadout_index = a as i32;
+        }
+        Ok(())
+    }
+
+    /// A copy of a loadout, to give to another player with `import_loadout`.
+    pub fn export_loadout(&self, index: usize) -> Result<Loadout, LoadoutError> {
+        self.check_loadout_index(index)?;
+        Ok(self.loadouts[index].clone())
+    }
+
+    /// Replaces a loadout with one exported from another player. This fails if the loadout
+    /// has items this player's version can't hold, such as items newer than it.
+    pub fn import_loadout(&mut self, index: usize, loadout: &Loadout) -> Result<(), LoadoutError> {
+        self.check_loadout_index(index)?;
+
+        let report = DowngradeReport::for_loadout(loadout, index, self.version);
+        if !report.is_lossless() {
+            return Err(LoadoutError::UnsupportedByVersion {
+                version: self.version,
+                changes: report.changes,
+            });
+        }
+
+        self.loadouts[index] = loadout.clone();
+        Ok(())
+    }
 }
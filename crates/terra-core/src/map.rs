@@ -0,0 +1,99 @@
+mod map_data;
+
+pub use map_data::{Map, MapError};
+
+use crate::{Color, LiquidType};
+
+/// What a single map tile shows.
+///
+/// Tile and wall options are the game's variants of a type that are drawn in different
+/// colours (e.g. each gem in Gemspark blocks).
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum MapTileKind {
+    #[default]
+    Unexplored,
+    Tile {
+        tile_type: u16,
+        option: u8,
+    },
+    Wall {
+        wall_type: u16,
+        option: u8,
+    },
+    Liquid(LiquidType),
+    /// Sky above the surface, or the Underworld's background near the bottom of the world.
+    Background,
+    /// Dirt or rock background, depending on the depth; the value is the gradient step.
+    Underground(u16),
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct MapTile {
+    pub kind: MapTileKind,
+    /// Brightness the tile was seen at, from 0 (dark) to 255 (fully lit).
+    pub light: u8,
+    /// Paint colour id, 0 if unpainted.
+    pub color: u8,
+}
+
+impl MapTile {
+    pub fn is_explored(&self) -> bool {
+        self.kind != MapTileKind::Unexplored
+    }
+}
+
+/// Depths used to decide which background colour to use when rendering.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MapLayers {
+    pub surface_level: f64,
+    pub rock_level: f64,
+    /// The Underworld starts 200 tiles above the bottom of the world.
+    pub underworld_level: f64,
+}
+
+impl MapLayers {
+    /// Rough layer depths for when the world itself isn't available.
+    pub fn estimate(height: i32) -> Self {
+        let height = height as f64;
+        Self {
+            surface_level: height * 0.3,
+            rock_level: height * 0.4,
+            underworld_level: height - 200.,
+        }
+    }
+}
+
+/// A simple palette which colours tiles by what they are, rather than by type.
+///
+/// terra-rs doesn't ship the game's per-tile map colours, so use `Map::render_with` to
+/// provide a more detailed palette.
+pub fn default_map_color(tile: &MapTile, y: i32, layers: &MapLayers) -> Color {
+    let y = y as f64;
+
+    match tile.kind {
+        MapTileKind::Unexplored => [0, 0, 0],
+        MapTileKind::Tile { .. } => [151, 107, 75],
+        MapTileKind::Wall { .. } => [52, 52, 52],
+        MapTileKind::Liquid(LiquidType::Water) => [9, 61, 191],
+        MapTileKind::Liquid(LiquidType::Lava) => [253, 32, 3],
+        MapTileKind::Liquid(LiquidType::Honey) => [254, 194, 20],
+        MapTileKind::Liquid(LiquidType::Shimmer) => [189, 150, 255],
+        MapTileKind::Liquid(LiquidType::None) => [0, 0, 0],
+        MapTileKind::Background if y >= layers.underworld_level => [50, 44, 38],
+        MapTileKind::Background => {
+            // Fade from a deep blue at the top of the world to light blue at the surface
+            let t = (y / layers.surface_level.max(1.)).clamp(0., 1.);
+            [
+                (50. + 82. * t) as u8,
+                (40. + 130. * t) as u8,
+                (255. - 7. * t) as u8,
+            ]
+        }
+        MapTileKind::Underground(_) if y < layers.rock_level => [88, 61, 46],
+        MapTileKind::Underground(_) => [74, 67, 60],
+    }
+}
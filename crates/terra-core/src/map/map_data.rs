@@ -0,0 +1,713 @@
+use std::{
+    fs::File,
+    io::{Cursor, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use image::{Rgba, RgbaImage};
+
+use crate::{
+    ext::{TerraReadExt, TerraWriteExt},
+    map::default_map_color,
+    Color, FileType, LiquidType, MapLayers, MapTile, MapTileKind, Tile, TileGrid, World,
+    CURRENT_VERSION, MAGIC_MASK, MAGIC_NUMBER,
+};
+
+/// Maps older than this use a different layout which isn't supported.
+const MIN_MAP_VERSION: i32 = 92;
+/// The tile data has been deflated since this version.
+const COMPRESSED_MAP_VERSION: i32 = 93;
+
+/// Lookup table sizes used by the current version of the game, for maps created from scratch.
+const WALL_TYPE_COUNT: usize = 347;
+const LIQUID_COUNT: i16 = 3;
+const SKY_GRADIENT_COUNT: i16 = 256;
+const DIRT_GRADIENT_COUNT: i16 = 255;
+const ROCK_GRADIENT_COUNT: i16 = 255;
+
+/// Liquids shallower than this don't show up on the map.
+const MIN_VISIBLE_LIQUID: u8 = 32;
+
+#[derive(thiserror::Error, Debug)]
+pub enum MapError {
+    #[error("Unknown error with file.")]
+    Failure,
+    #[error("The file cannot be read by the user.")]
+    AccessDenied,
+    #[error("The file was not found.")]
+    FileNotFound,
+    #[error("The file is for a newer version of Terraria ({0}) than terra-rs supports (<= {CURRENT_VERSION}).")]
+    PostDated(i32),
+    #[error("The file is for an older version of Terraria ({0}) than terra-rs supports (>= {MIN_MAP_VERSION}).")]
+    PreDated(i32),
+    #[error("The file is corrupted.")]
+    Corrupted,
+    #[error("Expected Re-Logic file format.")]
+    IncorrectFormat,
+    #[error("Found incorrect file type.")]
+    IncorrectFileType,
+    #[error("The map is for a different world.")]
+    WorldMismatch,
+    #[error("The map is {width}x{height}, but has {tiles} tiles.")]
+    InvalidDimensions {
+        width: i32,
+        height: i32,
+        tiles: usize,
+    },
+}
+
+/// A `.map` file, which stores what one player has explored of one world.
+#[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct Map {
+    pub version: i32,
+    pub revision: u32,
+    pub favourited: u64,
+
+    pub world_name: String,
+    pub world_id: i32,
+    pub width: i32,
+    pub height: i32,
+
+    /// How many map colours each tile type has.
+    pub tile_options: Vec<u8>,
+    /// How many map colours each wall type has.
+    pub wall_options: Vec<u8>,
+    pub liquid_count: i16,
+    pub sky_count: i16,
+    pub dirt_count: i16,
+    pub rock_count: i16,
+
+    /// Stored row by row, matching the order they appear in the file.
+    #[serde(skip)]
+    pub tiles: Vec<MapTile>,
+}
+
+fn map_io_error(e: std::io::Error) -> MapError {
+    match e.kind() {
+        ErrorKind::NotFound => MapError::FileNotFound,
+        ErrorKind::PermissionDenied => MapError::AccessDenied,
+        _ => MapError::Failure,
+    }
+}
+
+fn open_file(filepath: &Path) -> Result<File, MapError> {
+    File::open(filepath).map_err(map_io_error)
+}
+
+fn create_file(filepath: &Path) -> Result<File, MapError> {
+    File::create(filepath).map_err(map_io_error)
+}
+
+/// Reads an array of bools packed LSB-first, 8 to a byte.
+fn read_bits(reader: &mut dyn Read, count: usize) -> anyhow::Result<Vec<bool>> {
+    let mut bits = vec![false; count];
+    let mut byte = 0;
+
+    for (i, bit) in bits.iter_mut().enumerate() {
+        if i.is_multiple_of(8) {
+            byte = reader.read_u8()?;
+        }
+        *bit = byte & (1 << (i % 8)) != 0;
+    }
+
+    Ok(bits)
+}
+
+fn write_bits(writer: &mut dyn Write, bits: impl Iterator<Item = bool>) -> anyhow::Result<()> {
+    let bits: Vec<bool> = bits.collect();
+
+    for chunk in bits.chunks(8) {
+        let byte = chunk
+            .iter()
+            .enumerate()
+            .fold(0u8, |b, (i, &bit)| b | ((bit as u8) << i));
+        writer.write_u8(byte)?;
+    }
+
+    Ok(())
+}
+
+/// Finds which type (and option of that type) a map colour index refers to.
+fn lookup_option(options: &[u8], index: u16) -> Option<(u16, u8)> {
+    let mut start = 0u32;
+
+    for (ty, &count) in options.iter().enumerate() {
+        let end = start + count as u32;
+        if (index as u32) < end {
+            return Some((ty as u16, (index as u32 - start) as u8));
+        }
+        start = end;
+    }
+
+    None
+}
+
+/// The inverse of `lookup_option`.
+fn option_index(options: &[u8], ty: u16, option: u8) -> Option<u16> {
+    let count = *options.get(ty as usize)?;
+    if option >= count {
+        return None;
+    }
+
+    let start: u32 = options[..ty as usize].iter().map(|&c| c as u32).sum();
+    u16::try_from(start + option as u32).ok()
+}
+
+impl Map {
+    /// Creates an empty (entirely unexplored) map for a world.
+    pub fn for_world(world: &World) -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            revision: 0,
+            favourited: 0,
+            world_name: world.name.clone(),
+            world_id: world.id,
+            width: world.width,
+            height: world.height,
+            tile_options: vec![1; world.tile_frame_important.len()],
+            wall_options: vec![1; WALL_TYPE_COUNT],
+            liquid_count: LIQUID_COUNT,
+            sky_count: SKY_GRADIENT_COUNT,
+            dirt_count: DIRT_GRADIENT_COUNT,
+            rock_count: ROCK_GRADIENT_COUNT,
+            tiles: vec![
+                MapTile::default();
+                world.width.max(0) as usize * world.height.max(0) as usize
+            ],
+        }
+    }
+
+    fn load_from_reader(&mut self, reader: &mut dyn Read) -> anyhow::Result<()> {
+        self.version = reader.read_i32::<LE>()?;
+
+        if self.version > CURRENT_VERSION {
+            return Err(MapError::PostDated(self.version).into());
+        }
+
+        if self.version < MIN_MAP_VERSION {
+            return Err(MapError::PreDated(self.version).into());
+        }
+
+        if self.version >= 135 {
+            let magic_num = reader.read_u64::<LE>()?;
+
+            if magic_num & MAGIC_MASK != MAGIC_NUMBER {
+                return Err(MapError::IncorrectFormat.into());
+            }
+
+            if ((magic_num >> 56) as u8) != FileType::Map {
+                return Err(MapError::IncorrectFileType.into());
+            }
+
+            self.revision = reader.read_u32::<LE>()?;
+            self.favourited = reader.read_u64::<LE>()?;
+        }
+
+        self.world_name = reader.read_lpstring()?;
+        self.world_id = reader.read_i32::<LE>()?;
+        self.height = reader.read_i32::<LE>()?;
+        self.width = reader.read_i32::<LE>()?;
+
+        if self.width <= 0 || self.height <= 0 {
+            return Err(MapError::Corrupted.into());
+        }
+
+        let tile_count = reader.read_i16::<LE>()?.max(0) as usize;
+        let wall_count = reader.read_i16::<LE>()?.max(0) as usize;
+        self.liquid_count = reader.read_i16::<LE>()?;
+        self.sky_count = reader.read_i16::<LE>()?;
+        self.dirt_count = reader.read_i16::<LE>()?;
+        self.rock_count = reader.read_i16::<LE>()?;
+
+        let tile_has_options = read_bits(reader, tile_count)?;
+        let wall_has_options = read_bits(reader, wall_count)?;
+
+        self.tile_options = Vec::with_capacity(tile_count);
+        for has_options in tile_has_options {
+            self.tile_options
+                .push(if has_options { reader.read_u8()? } else { 1 });
+        }
+
+        self.wall_options = Vec::with_capacity(wall_count);
+        for has_options in wall_has_options {
+            self.wall_options
+                .push(if has_options { reader.read_u8()? } else { 1 });
+        }
+
+        if self.version >= COMPRESSED_MAP_VERSION {
+            let mut decoder = DeflateDecoder::new(reader);
+            self.load_tiles(&mut decoder)
+        } else {
+            self.load_tiles(reader)
+        }
+    }
+
+    fn load_tiles(&mut self, reader: &mut dyn Read) -> anyhow::Result<()> {
+        let width = self.width as usize;
+        let height = self.height as usize;
+        self.tiles = vec![MapTile::default(); width * height];
+
+        for y in 0..height {
+            let row = &mut self.tiles[y * width..(y + 1) * width];
+            let mut x = 0;
+
+            while x < width {
+                let header1 = reader.read_u8()?;
+                let header2 = if header1 & 0b1 != 0 {
+                    reader.read_u8()?
+                } else {
+                    0
+                };
+
+                let group = (header1 & 0b1110) >> 1;
+                let has_index = matches!(group, 1 | 2 | 7);
+
+                let index = if !has_index {
+                    0
+                } else if header1 & 0b1_0000 != 0 {
+                    reader.read_u16::<LE>()?
+                } else {
+                    reader.read_u8()? as u16
+                };
+
+                let has_light = header1 & 0b10_0000 != 0;
+                let light = if has_light { reader.read_u8()? } else { 255 };
+
+                let repeat = match (header1 & 0b1100_0000) >> 6 {
+                    1 => reader.read_u8()? as i16,
+                    2 => reader.read_i16::<LE>()?,
+                    _ => 0,
+                };
+                let repeat = usize::try_from(repeat).map_err(|_| MapError::Corrupted)?;
+
+                let end = x
+                    .checked_add(repeat)
+                    .filter(|&end| end < width)
+                    .ok_or(MapError::Corrupted)?;
+
+                let kind = match group {
+                    0 => {
+                        x = end + 1;
+                        continue;
+                    }
+                    1 => {
+                        let (tile_type, option) =
+                            lookup_option(&self.tile_options, index).ok_or(MapError::Corrupted)?;
+                        MapTileKind::Tile { tile_type, option }
+                    }
+                    2 => {
+                        let (wall_type, option) =
+                            lookup_option(&self.wall_options, index).ok_or(MapError::Corrupted)?;
+                        MapTileKind::Wall { wall_type, option }
+                    }
+                    // Shimmer is saved as water with a flag
+                    3 if header2 & 0b100_0000 != 0 => MapTileKind::Liquid(LiquidType::Shimmer),
+                    3 => MapTileKind::Liquid(LiquidType::Water),
+                    4 => MapTileKind::Liquid(LiquidType::Lava),
+                    5 => MapTileKind::Liquid(LiquidType::Honey),
+                    6 => MapTileKind::Background,
+                    _ => MapTileKind::Underground(index),
+                };
+
+                let mut tile = MapTile {
+                    kind,
+                    light,
+                    color: (header2 >> 1) & 0b1_1111,
+                };
+                row[x] = tile;
+
+                // Repeated tiles only share their light if it was omitted
+                for _ in 0..repeat {
+                    x += 1;
+                    if has_light {
+                        tile.light = reader.read_u8()?;
+                    }
+                    row[x] = tile;
+                }
+
+                x += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn save_to_writer(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        // Tiles are written a row at a time, so they have to fill the map exactly
+        let tile_count = usize::try_from(self.width)
+            .ok()
+            .zip(usize::try_from(self.height).ok())
+            .and_then(|(width, height)| width.checked_mul(height));
+        if self.width <= 0 || self.height <= 0 || tile_count != Some(self.tiles.len()) {
+            return Err(MapError::InvalidDimensions {
+                width: self.width,
+                height: self.height,
+                tiles: self.tiles.len(),
+            }
+            .into());
+        }
+
+        writer.write_i32::<LE>(self.version)?;
+
+        if self.version >= 135 {
+            writer.write_u64::<LE>(MAGIC_NUMBER | (u64::from(FileType::Map) << 56u64))?;
+            writer.write_u32::<LE>(self.revision)?;
+            writer.write_u64::<LE>(self.favourited)?;
+        }
+
+        writer.write_lpstring(&self.world_name)?;
+        writer.write_i32::<LE>(self.world_id)?;
+        writer.write_i32::<LE>(self.height)?;
+        writer.write_i32::<LE>(self.width)?;
+
+        writer.write_i16::<LE>(self.tile_options.len() as i16)?;
+        writer.write_i16::<LE>(self.wall_options.len() as i16)?;
+        writer.write_i16::<LE>(self.liquid_count)?;
+        writer.write_i16::<LE>(self.sky_count)?;
+        writer.write_i16::<LE>(self.dirt_count)?;
+        writer.write_i16::<LE>(self.rock_count)?;
+
+        write_bits(writer, self.tile_options.iter().map(|&c| c > 1))?;
+        write_bits(writer, self.wall_options.iter().map(|&c| c > 1))?;
+
+        for &count in self.tile_options.iter().chain(self.wall_options.iter()) {
+            if count > 1 {
+                writer.write_u8(count)?;
+            }
+        }
+
+        if self.version >= COMPRESSED_MAP_VERSION {
+            let mut encoder = DeflateEncoder::new(writer, Compression::default());
+            self.save_tiles(&mut encoder)?;
+            encoder.finish()?;
+        } else {
+            self.save_tiles(writer)?;
+        }
+
+        Ok(())
+    }
+
+    fn save_tiles(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        let width = self.width as usize;
+
+        for row in self.tiles.chunks(width) {
+            let mut x = 0;
+
+            while x < width {
+                let tile = row[x];
+
+                // Tiles with omitted light are only repeated if they are also fully lit,
+                // otherwise each repeated tile is followed by its own light
+                let has_light = tile.is_explored() && tile.light != 255;
+                let repeat = row[x + 1..]
+                    .iter()
+                    .take(i16::MAX as usize)
+                    .take_while(|t| {
+                        if tile.is_explored() {
+                            t.kind == tile.kind
+                                && t.color == tile.color
+                                && (t.light != 255) == has_light
+                        } else {
+                            !t.is_explored()
+                        }
+                    })
+                    .count();
+
+                let (group, index) = match tile.kind {
+                    MapTileKind::Unexplored => (0, None),
+                    MapTileKind::Tile { tile_type, option } => (
+                        1,
+                        Some(
+                            option_index(&self.tile_options, tile_type, option)
+                                .ok_or(MapError::Corrupted)?,
+                        ),
+                    ),
+                    MapTileKind::Wall { wall_type, option } => (
+                        2,
+                        Some(
+                            option_index(&self.wall_options, wall_type, option)
+                                .ok_or(MapError::Corrupted)?,
+                        ),
+                    ),
+                    MapTileKind::Liquid(LiquidType::Lava) => (4, None),
+                    MapTileKind::Liquid(LiquidType::Honey) => (5, None),
+                    MapTileKind::Liquid(_) => (3, None),
+                    MapTileKind::Background => (6, None),
+                    MapTileKind::Underground(gradient) => (7, Some(gradient)),
+                };
+
+                let mut header2 = (tile.color & 0b1_1111) << 1;
+                if tile.kind == MapTileKind::Liquid(LiquidType::Shimmer) {
+                    header2 |= 0b100_0000;
+                }
+
+                let mut header1 = group << 1;
+                if header2 != 0 {
+                    header1 |= 0b1;
+                }
+                if index.is_some_and(|i| i > u8::MAX as u16) {
+                    header1 |= 0b1_0000;
+                }
+                if has_light {
+                    header1 |= 0b10_0000;
+                }
+                match repeat {
+                    0 => {}
+                    1..=0xFF => header1 |= 0b0100_0000,
+                    _ => header1 |= 0b1000_0000,
+                }
+
+                writer.write_u8(header1)?;
+                if header2 != 0 {
+                    writer.write_u8(header2)?;
+                }
+
+                match index {
+                    Some(index) if index > u8::MAX as u16 => writer.write_u16::<LE>(index)?,
+                    Some(index) => writer.write_u8(index as u8)?,
+                    None => {}
+                }
+
+                if has_light {
+                    writer.write_u8(tile.light)?;
+                }
+
+                match repeat {
+                    0 => {}
+                    1..=0xFF => writer.write_u8(repeat as u8)?,
+                    _ => writer.write_i16::<LE>(repeat as i16)?,
+                }
+
+                if has_light {
+                    for t in &row[x + 1..=x + repeat] {
+                        writer.write_u8(t.light)?;
+                    }
+                }
+
+                x += repeat + 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn load(&mut self, filepath: &Path) -> anyhow::Result<()> {
+        let mut file = open_file(filepath)?;
+        let mut data = Vec::with_capacity(file.metadata().map(|m| m.len() as usize).unwrap_or(0));
+        file.read_to_end(&mut data)?;
+
+        let mut reader = Cursor::new(data);
+        self.load_from_reader(&mut reader)
+    }
+
+    /// Saves the map, incrementing its revision as the game does.
+    pub fn save(&mut self, filepath: &Path) -> anyhow::Result<()> {
+        self.revision = self.revision.wrapping_add(1);
+
+        let mut buf = Vec::new();
+        self.save_to_writer(&mut buf)?;
+
+        let mut file = create_file(filepath)?;
+        file.write_all(&buf)?;
+
+        Ok(())
+    }
+
+    /// Every `.map` file belonging to a player, which the game keeps in a folder
+    /// next to the `.plr` file with the same name.
+    pub fn find_for_player(player_filepath: &Path) -> anyhow::Result<Vec<PathBuf>> {
+        let folder = player_filepath.with_extension("");
+        if !folder.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(folder)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "map") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        Ok(paths)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&MapTile> {
+        if x < self.width as usize && y < self.height as usize {
+            self.tiles.get(y * self.width as usize + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn explored_count(&self) -> usize {
+        self.tiles.iter().filter(|t| t.is_explored()).count()
+    }
+
+    /// How much of the world has been explored, from 0 to 1.
+    pub fn explored_fraction(&self) -> f64 {
+        if self.tiles.is_empty() {
+            0.
+        } else {
+            self.explored_count() as f64 / self.tiles.len() as f64
+        }
+    }
+
+    pub fn layers(&self, world: Option<&World>) -> MapLayers {
+        match world {
+            Some(world) => MapLayers {
+                surface_level: world.surface_level,
+                rock_level: world.rock_level,
+                underworld_level: (world.height - 200) as f64,
+            },
+            None => MapLayers::estimate(self.height),
+        }
+    }
+
+    fn background(&self, y: usize, layers: &MapLayers) -> MapTileKind {
+        let y = y as f64;
+
+        let gradient = |count: i16, top: f64, bottom: f64| {
+            let t = ((y - top) / (bottom - top).max(1.)).clamp(0., 1.);
+            (t * (count.max(1) - 1) as f64) as u16
+        };
+
+        if y < layers.surface_level || y >= layers.underworld_level {
+            MapTileKind::Background
+        } else if y < layers.rock_level {
+            MapTileKind::Underground(gradient(
+                self.dirt_count,
+                layers.surface_level,
+                layers.rock_level,
+            ))
+        } else {
+            MapTileKind::Underground(gradient(
+                self.rock_count,
+                layers.rock_level,
+                layers.underworld_level,
+            ))
+        }
+    }
+
+    /// What the map shows for a world tile, ignoring lighting.
+    fn map_tile(&self, tile: &Tile, y: usize, layers: &MapLayers) -> MapTile {
+        let (kind, color) = if tile.active && !tile.invisible_block {
+            (
+                MapTileKind::Tile {
+                    tile_type: tile.tile_type,
+                    option: 0,
+                },
+                tile.tile_color,
+            )
+        } else if tile.liquid_amount > MIN_VISIBLE_LIQUID && tile.liquid != LiquidType::None {
+            (MapTileKind::Liquid(tile.liquid), 0)
+        } else if tile.wall > 0 && !tile.invisible_wall {
+            (
+                MapTileKind::Wall {
+                    wall_type: tile.wall,
+                    option: 0,
+                },
+                tile.wall_color,
+            )
+        } else {
+            (self.background(y, layers), 0)
+        };
+
+        MapTile {
+            kind,
+            light: 255,
+            color,
+        }
+    }
+
+    /// Marks every tile as explored, using the world's tiles to decide what each one shows.
+    ///
+    /// Tiles and walls use the first of their map colours, the game will pick the
+    /// correct variant once the area is seen again.
+    pub fn reveal_from_world(&mut self, world: &World, tiles: &TileGrid) -> anyhow::Result<()> {
+        if world.id != self.world_id
+            || world.width != self.width
+            || world.height != self.height
+            || tiles.width() != self.width as usize
+            || tiles.height() != self.height as usize
+        {
+            return Err(MapError::WorldMismatch.into());
+        }
+
+        // Make sure every type in the world can be looked up
+        let max_tile = tiles.iter().map(|(_, _, t)| t.tile_type).max().unwrap_or(0);
+        let max_wall = tiles.iter().map(|(_, _, t)| t.wall).max().unwrap_or(0);
+        if self.tile_options.len() <= max_tile as usize {
+            self.tile_options.resize(max_tile as usize + 1, 1);
+        }
+        if self.wall_options.len() <= max_wall as usize {
+            self.wall_options.resize(max_wall as usize + 1, 1);
+        }
+
+        let layers = self.layers(Some(world));
+        let width = self.width as usize;
+
+        for (x, y, tile) in tiles.iter() {
+            self.tiles[y * width + x] = self.map_tile(tile, y, &layers);
+        }
+
+        Ok(())
+    }
+
+    /// Marks every unexplored tile as explored without knowing what is there, so they
+    /// only show the background for their depth.
+    pub fn reveal(&mut self, layers: &MapLayers) {
+        let width = self.width as usize;
+
+        for i in 0..self.tiles.len() {
+            if !self.tiles[i].is_explored() {
+                self.tiles[i] = MapTile {
+                    kind: self.background(i / width, layers),
+                    light: 255,
+                    color: 0,
+                };
+            }
+        }
+    }
+
+    /// Renders the explored tiles with `default_map_color`, leaving the rest transparent.
+    pub fn render(&self, layers: &MapLayers) -> RgbaImage {
+        self.render_with(layers, default_map_color)
+    }
+
+    /// Renders the explored tiles using the given palette, leaving the rest transparent.
+    /// Colours are darkened by how brightly the tile was lit when it was seen.
+    pub fn render_with<F>(&self, layers: &MapLayers, color: F) -> RgbaImage
+    where
+        F: Fn(&MapTile, i32, &MapLayers) -> Color,
+    {
+        let mut image = RgbaImage::new(self.width as u32, self.height as u32);
+
+        for (i, tile) in self.tiles.iter().enumerate() {
+            if !tile.is_explored() {
+                continue;
+            }
+
+            let x = (i % self.width as usize) as u32;
+            let y = (i / self.width as usize) as u32;
+
+            let [r, g, b] = color(tile, y as i32, layers);
+            let light = tile.light as u16;
+            let shade = |c: u8| (c as u16 * light / 255) as u8;
+
+            image.put_pixel(x, y, Rgba([shade(r), shade(g), shade(b), 255]));
+        }
+
+        image
+    }
+
+    pub fn save_png(&self, layers: &MapLayers, filepath: &Path) -> anyhow::Result<()> {
+        self.render(layers).save(filepath)?;
+        Ok(())
+    }
+}
@@ -1,12 +1,19 @@
-use crate::SharedString;
+use std::{collections::HashMap, fmt, hash::Hash, ops::Deref};
+
+use crate::{BuffMeta, ItemMeta, PrefixMeta, SharedString};
 
 pub trait Meta {
-    type Id: Ord;
+    type Id: Ord + Hash + Copy + fmt::Debug;
 
     fn id(&self) -> Self::Id;
     fn name(&self) -> SharedString;
     fn internal_name(&self) -> SharedString;
 
+    /// Names the entry used to go by, which `MetaRegistry` indexes alongside its name.
+    fn legacy_names(&self) -> Vec<SharedString> {
+        Vec::new()
+    }
+
     fn get(meta: &[Self], id: Self::Id) -> Option<&Self>
     where
         Self: Sized,
@@ -37,3 +44,123 @@ pub trait Meta {
         meta.iter().find(|m| m.internal_name() == internal_name)
     }
 }
+
+/// A list of metadata, indexed by id, name and internal name.
+///
+/// Derefs to the underlying slice (sorted by id), so it can be iterated like one.
+#[derive(Debug, Clone)]
+pub struct MetaRegistry<T: Meta> {
+    meta: Vec<T>,
+    by_id: HashMap<T::Id, usize>,
+    by_name: HashMap<SharedString, usize>,
+    by_internal_name: HashMap<SharedString, usize>,
+    by_legacy_name: HashMap<SharedString, Vec<usize>>,
+}
+
+impl<T: Meta> Default for MetaRegistry<T> {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl<T: Meta> From<Vec<T>> for MetaRegistry<T> {
+    fn from(meta: Vec<T>) -> Self {
+        Self::new(meta)
+    }
+}
+
+impl<T: Meta> Deref for MetaRegistry<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.meta
+    }
+}
+
+impl<T: Meta> MetaRegistry<T> {
+    pub fn new(mut meta: Vec<T>) -> Self {
+        meta.sort_by_key(|m| m.id());
+
+        let mut by_id = HashMap::with_capacity(meta.len());
+        let mut by_name = HashMap::with_capacity(meta.len());
+        let mut by_internal_name = HashMap::with_capacity(meta.len());
+        let mut by_legacy_name: HashMap<SharedString, Vec<usize>> = HashMap::new();
+
+        // Like the linear lookups, the first entry wins if several share a name
+        for (i, m) in meta.iter().enumerate() {
+            by_id.entry(m.id()).or_insert(i);
+            by_name.entry(m.name()).or_insert(i);
+            by_internal_name.entry(m.internal_name()).or_insert(i);
+            for name in m.legacy_names() {
+                by_legacy_name.entry(name).or_default().push(i);
+            }
+        }
+
+        Self {
+            meta,
+            by_id,
+            by_name,
+            by_internal_name,
+            by_legacy_name,
+        }
+    }
+
+    pub fn get(&self, id: T::Id) -> Option<&T> {
+        self.by_id.get(&id).map(|&i| &self.meta[i])
+    }
+
+    /// Falls back to the first (zeroth) entry if the id isn't known.
+    pub fn get_or_default(&self, id: T::Id) -> &T {
+        self.get(id).unwrap_or(
+            self.meta
+                .first()
+                .expect("We really should have a zeroth meta"),
+        )
+    }
+
+    pub fn get_by_name(&self, name: &str) -> Option<&T> {
+        self.by_name.get(name).map(|&i| &self.meta[i])
+    }
+
+    pub fn get_by_internal_name(&self, internal_name: &str) -> Option<&T> {
+        self.by_internal_name
+            .get(internal_name)
+            .map(|&i| &self.meta[i])
+    }
+
+    /// Every entry which used to go by `name`, in id order.
+    pub fn get_by_legacy_name(&self, name: &str) -> impl Iterator<Item = &T> {
+        self.by_legacy_name
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.meta[i])
+    }
+
+    /// The largest id, or `None` if the registry is empty.
+    pub fn max_id(&self) -> Option<T::Id> {
+        self.meta.last().map(|m| m.id())
+    }
+
+    pub fn into_inner(self) -> Vec<T> {
+        self.meta
+    }
+}
+
+/// All of the metadata terra-rs uses, as loaded from the generated resources.
+#[derive(Debug, Clone, Default)]
+pub struct MetaBundle {
+    pub items: MetaRegistry<ItemMeta>,
+    pub buffs: MetaRegistry<BuffMeta>,
+    pub prefixes: MetaRegistry<PrefixMeta>,
+}
+
+impl MetaBundle {
+    pub fn new(items: Vec<ItemMeta>, buffs: Vec<BuffMeta>, prefixes: Vec<PrefixMeta>) -> Self {
+        Self {
+            items: MetaRegistry::new(items),
+            buffs: MetaRegistry::new(buffs),
+            prefixes: MetaRegistry::new(prefixes),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::{fmt::Display, io::Read};
+
+/// Counts how many bytes have been read, so that errors can say where they happened.
+pub(crate) struct PositionReader<R> {
+    inner: R,
+    position: u64,
+}
+
+impl<R: Read> PositionReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner, position: 0 }
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+impl<R: Read> Read for PositionReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+/// One segment of the path to a field which failed to load.
+#[derive(Debug)]
+pub(crate) struct FieldError {
+    segment: String,
+    source: anyhow::Error,
+}
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to load `{}`", self.segment)
+    }
+}
+
+impl std::error::Error for FieldError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+pub(crate) trait FieldContext<T> {
+    /// Marks an error as having happened while loading the named field.
+    fn field(self, name: &str) -> anyhow::Result<T>;
+
+    /// Marks an error as having happened while loading `name[index]`.
+    fn field_index(self, name: &str, index: usize) -> anyhow::Result<T>;
+}
+
+impl<T, E: Into<anyhow::Error>> FieldContext<T> for Result<T, E> {
+    fn field(self, name: &str) -> anyhow::Result<T> {
+        self.map_err(|e| {
+            FieldError {
+                segment: name.to_owned(),
+                source: e.into(),
+            }
+            .into()
+        })
+    }
+
+    fn field_index(self, name: &str, index: usize) -> anyhow::Result<T> {
+        self.map_err(|e| {
+            FieldError {
+                segment: format!("{name}[{index}]"),
+                source: e.into(),
+            }
+            .into()
+        })
+    }
+}
+
+/// Unwraps the field segments from an error, returning the full path
+/// (e.g. `loadouts[2].accessory_dyes[4].prefix`) and the underlying error.
+pub(crate) fn take_field_path(mut error: anyhow::Error) -> (String, anyhow::Error) {
+    let mut path = String::new();
+
+    loop {
+        match error.downcast::<FieldError>() {
+            Ok(field) => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path += &field.segment;
+                error = field.source;
+            }
+            Err(inner) => return (path, inner),
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use serde_json::{Map, Value};
+
+use crate::{ContainerKind, DowngradeChange, Player};
+
+#[derive(thiserror::Error, Debug)]
+pub enum PatchError {
+    #[error("Operation {index}: `{pointer}` isn't a valid JSON pointer.")]
+    InvalidPointer { index: usize, pointer: String },
+    #[error("Operation {index}: there's nothing at `{path}`.")]
+    PathNotFound { index: usize, path: String },
+    #[error("Operation {index}: the value at `{path}` doesn't match the test.")]
+    TestFailed { index: usize, path: String },
+    #[error("Operation {index}: can't move `{from}` into itself at `{path}`.")]
+    MoveIntoItself {
+        index: usize,
+        from: String,
+        path: String,
+    },
+    #[error("The patched player isn't valid: {0}")]
+    InvalidPlayer(#[from] serde_json::Error),
+    #[error("The patch changes the version from {from} to {to}, which only downgrading can do.")]
+    VersionChanged { from: i32, to: i32 },
+    #[error(
+        "The patch sets values a version {version} player can't hold: {}",
+        .changes.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    UnsupportedByVersion {
+        version: i32,
+        changes: Vec<DowngradeChange>,
+    },
+}
+
+/// An RFC 6902 JSON Patch operation, where paths are JSON pointers into the player's JSON.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+/// A change to apply to players, usually read from a `.json` file with `from_json`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub enum PlayerPatch {
+    /// An RFC 6902 JSON Patch, like `[{"op": "replace", "path": "/max_life", "value": 500}]`.
+    Operations(Vec<PatchOperation>),
+    /// Part of a player, like `{"max_life": 500}`.
+    ///
+    /// Objects are merged field by field and arrays element by element, so
+    /// `{"loadouts": [{"accessories": [...]}]}` only changes the first loadout's
+    /// accessories. Use `null` for an array element that should be left as it is. Anything
+    /// else replaces the player's value.
+    Partial(Value),
+}
+
+impl PlayerPatch {
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+/// Splits a pointer into its parent's pointer and the last, unescaped, token.
+fn split_pointer(index: usize, pointer: &str) -> Result<(&str, String), PatchError> {
+    let Some((parent, token)) = pointer.rsplit_once('/') else {
+        return Err(PatchError::InvalidPointer {
+            index,
+            pointer: pointer.to_owned(),
+        });
+    };
+    Ok((parent, token.replace("~1", "/").replace("~0", "~")))
+}
+
+/// The array index a token refers to, where `len` is allowed when adding.
+fn array_index(token: &str, len: usize) -> Option<usize> {
+    if token == "-" {
+        return Some(len);
+    }
+    if token.len() > 1 && token.starts_with('0') {
+        return None;
+    }
+    token.parse().ok().filter(|i| *i <= len)
+}
+
+fn add(document: &mut Value, index: usize, path: &str, value: Value) -> Result<(), PatchError> {
+    if path.is_empty() {
+        *document = value;
+        return Ok(());
+    }
+
+    let not_found = || PatchError::PathNotFound {
+        index,
+        path: path.to_owned(),
+    };
+    let (parent, token) = split_pointer(index, path)?;
+
+    match document.pointer_mut(parent).ok_or_else(not_found)? {
+        Value::Object(map) => {
+            map.insert(token, value);
+        }
+        Value::Array(array) => {
+            let i = array_index(&token, array.len()).ok_or_else(not_found)?;
+            array.insert(i, value);
+        }
+        _ => return Err(not_found()),
+    }
+    Ok(())
+}
+
+fn remove(document: &mut Value, index: usize, path: &str) -> Result<Value, PatchError> {
+    let not_found = || PatchError::PathNotFound {
+        index,
+        path: path.to_owned(),
+    };
+    let (parent, token) = split_pointer(index, path)?;
+
+    match document.pointer_mut(parent).ok_or_else(not_found)? {
+        Value::Object(map) => map.remove(&token).ok_or_else(not_found),
+        Value::Array(array) => {
+            let i = array_index(&token, array.len())
+                .filter(|i| *i < array.len())
+                .ok_or_else(not_found)?;
+            Ok(array.remove(i))
+        }
+        _ => Err(not_found()),
+    }
+}
+
+fn apply_operation(
+    document: &mut Value,
+    index: usize,
+    operation: &PatchOperation,
+) -> Result<(), PatchError> {
+    let not_found = |path: &str| PatchError::PathNotFound {
+        index,
+        path: path.to_owned(),
+    };
+
+    match operation {
+        PatchOperation::Add { path, value } => add(document, index, path, value.clone())?,
+        PatchOperation::Remove { path } => {
+            remove(document, index, path)?;
+        }
+        PatchOperation::Replace { path, value } => {
+            *document.pointer_mut(path).ok_or_else(|| not_found(path))? = value.clone();
+        }
+        PatchOperation::Move { from, path } => {
+            if path.starts_with(&format!("{from}/")) {
+                return Err(PatchError::MoveIntoItself {
+                    index,
+                    from: from.clone(),
+                    path: path.clone(),
+                });
+            }
+            let value = remove(document, index, from)?;
+            add(document, index, path, value)?;
+        }
+        PatchOperation::Copy { from, path } => {
+            let value = document.pointer(from).ok_or_else(|| not_found(from))?;
+            add(document, index, path, value.clone())?;
+        }
+        PatchOperation::Test { path, value } => {
+            if document.pointer(path) != Some(value) {
+                return Err(PatchError::TestFailed {
+                    index,
+                    path: path.clone(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn merge(target: &mut Value, partial: &Value) {
+    match (target, partial) {
+        (Value::Object(target), Value::Object(partial)) => merge_object(target, partial),
+        (Value::Array(target), Value::Array(partial)) => {
+            for (i, value) in partial.iter().enumerate() {
+                match target.get_mut(i) {
+                    Some(_) if value.is_null() => {}
+                    Some(target) => merge(target, value),
+                    None => target.push(value.clone()),
+                }
+            }
+        }
+        (target, partial) => *target = partial.clone(),
+    }
+}
+
+fn merge_object(target: &mut Map<String, Value>, partial: &Map<String, Value>) {
+    for (key, value) in partial {
+        match target.get_mut(key) {
+            Some(target) => merge(target, value),
+            None => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Gives the patched player the tModLoader data that JSON doesn't hold, keeping modded
+/// items in slots whose item didn't change.
+fn keep_mod_data(old: &mut Player, new: &mut Player) {
+    new.mod_data = old.mod_data.take();
+
+    for container in ContainerKind::iter() {
+        let (Some(old_items), Some(new_items)) =
+            (container.items_mut(old), container.items_mut(new))
+        else {
+            continue;
+        };
+        for (old, new) in old_items.iter_mut().zip(new_items.iter_mut()) {
+            if old.id == new.id {
+                new.mod_item = old.mod_item.take();
+            }
+        }
+    }
+}
+
+impl Player {
+    /// Applies a patch to the player, going through the same JSON the player serializes to.
+    ///
+    /// The patch can't change the version, and the result has to be something a player of
+    /// that version can hold, for example no items newer than the version. If anything
+    /// fails, the player is left unchanged.
+    pub fn apply_patch(&mut self, patch: &PlayerPatch) -> anyhow::Result<()> {
+        let mut document = serde_json::to_value(&*self)?;
+
+        match patch {
+            PlayerPatch::Operations(operations) => {
+                for (index, operation) in operations.iter().enumerate() {
+                    apply_operation(&mut document, index, operation)?;
+                }
+            }
+            PlayerPatch::Partial(partial) => merge(&mut document, partial),
+        }
+
+        let mut patched: Player = serde_json::from_value(document).map_err(PatchError::from)?;
+
+        if patched.version != self.version {
+            return Err(PatchError::VersionChanged {
+                from: self.version,
+                to: patched.version,
+            }
+            .into());
+        }
+
+        let report = patched.downgrade_report(patched.version)?;
+        if !report.is_lossless() {
+            return Err(PatchError::UnsupportedByVersion {
+                version: patched.version,
+                changes: report.changes,
+            }
+            .into());
+        }
+
+        keep_mod_data(self, &mut patched);
+        *self = patched;
+
+        Ok(())
+    }
+}
@@ -10,12 +10,14 @@ use serde_big_array::BigArray;
 use crate::{
     aes::{decrypt_from_reader, encrypt_to_writer},
     ext::{TerraReadExt, TerraWriteExt},
+    meta::MetaRegistry,
+    parse::{take_field_path, FieldContext, PositionReader},
     utils, BoolByte, Buff, Color, Difficulty, FileType, Item, ItemMeta, JourneyPowers, Loadout,
-    ResearchItem, Spawnpoint, Team, AMMO_COUNT, BANK_COUNT, BUFF_COUNT, BUILDER_ACCESSORY_COUNT,
-    CELLPHONE_INFO_COUNT, COINS_COUNT, CURRENT_VERSION, DPAD_BINDINGS_COUNT, EQUIPMENT_COUNT,
-    FEMALE_SKIN_VARIANTS, INVENTORY_COUNT, LOADOUT_COUNT, MAGIC_MASK, MAGIC_NUMBER,
-    MALE_SKIN_VARIANTS, MAX_RESPAWN_TIME, MOBILE_FILE_ALIGNMENT, SPAWNPOINT_LIMIT,
-    TEMPORARY_SLOT_COUNT,
+    ModPlayer, ResearchItem, Spawnpoint, Team, AMMO_COUNT, BANK_COUNT, BUFF_COUNT,
+    BUILDER_ACCESSORY_COUNT, CELLPHONE_INFO_COUNT, COINS_COUNT, CURRENT_VERSION,
+    DPAD_BINDINGS_COUNT, EQUIPMENT_COUNT, FEMALE_SKIN_VARIANTS, INVENTORY_COUNT, LOADOUT_COUNT,
+    MAGIC_MASK, MAGIC_NUMBER, MALE_SKIN_VARIANTS, MAX_RESPAWN_TIME, MOBILE_FILE_ALIGNMENT,
+    SPAWNPOINT_LIMIT, TEMPORARY_SLOT_COUNT,
 };
 
 #[derive(thiserror::Error, Debug)]
@@ -34,6 +36,27 @@ pub enum PlayerError {
     IncorrectFormat,
     #[error("Found incorrect file type.")]
     IncorrectFileType,
+    #[error("Can't downgrade a version {from} player to version {to}.")]
+    InvalidDowngrade { from: i32, to: i32 },
+    /// `offset` is relative to the start of the decrypted data.
+    #[error("Failed to load `{field}` at byte {offset} of a version {version} player: {source}")]
+    Parse {
+        field: String,
+        offset: u64,
+        version: i32,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+/// How a player's data is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerFormat {
+    /// Encrypted, as saved by the PC version of the game (`.plr`).
+    Encrypted,
+    /// Encrypted and padded with zeroes, as saved by the mobile version of the game.
+    Mobile,
+    /// Not encrypted, as saved by terra-rs (`.dplr`).
+    Decrypted,
 }
 
 #[derive(Debug, Clone)]
@@ -131,6 +154,9 @@ pub struct Player {
 
     pub golfer_score: i32,
 
+    /// Whether the player's research is shared with their team, saved before the research
+    /// list since version 282.
+    pub share_research: bool,
     pub research: Vec<ResearchItem>,
 
     pub temporary_slots: [Item; TEMPORARY_SLOT_COUNT],
@@ -148,6 +174,10 @@ pub struct Player {
     pub voice_pitch_offset: f32,
     pub pending_refunds: Vec<Item>,
     pub one_time_dialogues_seen: Vec<String>,
+
+    /// The tModLoader `.tplr` file, if the player has one.
+    #[serde(skip)]
+    pub mod_data: Option<ModPlayer>,
 }
 
 impl Default for Player {
@@ -245,6 +275,7 @@ impl Default for Player {
 
             golfer_score: 0,
 
+            share_research: false,
             research: Vec::new(),
 
             temporary_slots: std::array::from_fn(|_| Item::default()),
@@ -263,6 +294,8 @@ impl Default for Player {
             voice_pitch_offset: 0.,
             pending_refunds: Vec::new(),
             one_time_dialogues_seen: Vec::new(),
+
+            mod_data: None,
         }
     }
 }
@@ -286,7 +319,7 @@ fn create_file(filepath: &Path) -> Result<File, PlayerError> {
 impl Player {
     fn load_from_reader(
         &mut self,
-        item_meta: &[ItemMeta],
+        item_meta: &MetaRegistry<ItemMeta>,
         reader: &mut dyn Read,
     ) -> anyhow::Result<()> {
         self.version = reader.read_i32::<LE>()?;
@@ -297,7 +330,7 @@ impl Player {
 
         if self.version >= 135 {
             // The string "relogic", followed by a 1-byte filetype
-            let magic_num = reader.read_u64::<LE>()?;
+            let magic_num = reader.read_u64::<LE>().field("magic")?;
 
             // Both MAGIC_MASK and MAGIC_NUMBER were taken directly from Terraria's exe
             if magic_num & MAGIC_MASK != MAGIC_NUMBER {
@@ -309,43 +342,45 @@ impl Player {
             }
 
             // This u32 is a 'revision' field, that is only used for type 1 files (Map)
-            self.revision = reader.read_u32::<LE>()?;
+            self.revision = reader.read_u32::<LE>().field("revision")?;
             // This u64 is a 'favourited' field, which for Players, is handled by favourites.json
-            self.favourited = reader.read_u64::<LE>()?;
+            self.favourited = reader.read_u64::<LE>().field("favourited")?;
         }
 
         // This method mimics C#'s BinaryReader.ReadString(),
         // prefixing the string with its length in ULEB128 format
-        self.name = reader.read_lpstring()?;
+        self.name = reader.read_lpstring().field("name")?;
 
         if self.version >= 10 {
             if self.version >= 17 {
-                self.difficulty = Difficulty::from(reader.read_u8()?);
-            } else if reader.read_bool()? {
+                self.difficulty = Difficulty::from(reader.read_u8().field("difficulty")?);
+            } else if reader.read_bool().field("difficulty")? {
                 self.difficulty = Difficulty::Hardcore;
             }
         }
 
         if self.version >= 138 {
-            self.playtime = reader.read_i64::<LE>()?;
+            self.playtime = reader.read_i64::<LE>().field("playtime")?;
         }
 
-        self.hair_style = reader.read_i32::<LE>()?;
+        self.hair_style = reader.read_i32::<LE>().field("hair_style")?;
 
         if self.version >= 82 {
-            self.hair_dye = reader.read_u8()?;
+            self.hair_dye = reader.read_u8().field("hair_dye")?;
         }
 
         if self.version >= 283 {
-            self.team = Team::from(reader.read_u8()?);
+            self.team = Team::from(reader.read_u8().field("team")?);
         }
 
         if self.version >= 83 {
-            self.loadouts[0].load_visuals(reader, self.version, true)?;
+            self.loadouts[0]
+                .load_visuals(reader, self.version, true)
+                .field_index("loadouts", 0)?;
         }
 
         if self.version >= 119 {
-            let bb = BoolByte::from(reader.read_u8()?);
+            let bb = BoolByte::from(reader.read_u8().field("hide_equipment")?);
 
             for i in 0..(EQUIPMENT_COUNT as u8) {
                 self.hide_equipment[i as usize] = bb.get(i)?;
@@ -358,12 +393,12 @@ impl Player {
                 self.skin_variant = 4;
             }
         } else if self.version <= 106 {
-            self.male = reader.read_bool()?;
+            self.male = reader.read_bool().field("male")?;
             if self.male {
                 self.skin_variant = 4
             }
         } else {
-            self.skin_variant = reader.read_u8()?;
+            self.skin_variant = reader.read_u8().field("skin_variant")?;
 
             self.male = MALE_SKIN_VARIANTS.contains(&(self.skin_variant as i32));
         }
@@ -372,96 +407,101 @@ impl Player {
             self.skin_variant = 9;
         }
 
-        self.life = reader.read_i32::<LE>()?;
-        self.max_life = reader.read_i32::<LE>()?;
-        self.mana = reader.read_i32::<LE>()?;
-        self.max_mana = reader.read_i32::<LE>()?;
+        self.life = reader.read_i32::<LE>().field("life")?;
+        self.max_life = reader.read_i32::<LE>().field("max_life")?;
+        self.mana = reader.read_i32::<LE>().field("mana")?;
+        self.max_mana = reader.read_i32::<LE>().field("max_mana")?;
 
         if self.version >= 125 {
-            self.demon_heart = reader.read_bool()?;
+            self.demon_heart = reader.read_bool().field("demon_heart")?;
 
             if self.version >= 229 {
-                self.biome_torches = reader.read_bool()?;
-                self.biome_torches_enabled = reader.read_bool()?;
+                self.biome_torches = reader.read_bool().field("biome_torches")?;
+                self.biome_torches_enabled = reader.read_bool().field("biome_torches_enabled")?;
 
                 if self.version >= 256 {
-                    self.artisan_loaf = reader.read_bool()?;
+                    self.artisan_loaf = reader.read_bool().field("artisan_loaf")?;
 
                     if self.version >= 260 {
-                        self.vital_crystal = reader.read_bool()?;
-                        self.aegis_fruit = reader.read_bool()?;
-                        self.arcane_crystal = reader.read_bool()?;
-                        self.galaxy_pearl = reader.read_bool()?;
-                        self.gummy_worm = reader.read_bool()?;
-                        self.ambrosia = reader.read_bool()?;
+                        self.vital_crystal = reader.read_bool().field("vital_crystal")?;
+                        self.aegis_fruit = reader.read_bool().field("aegis_fruit")?;
+                        self.arcane_crystal = reader.read_bool().field("arcane_crystal")?;
+                        self.galaxy_pearl = reader.read_bool().field("galaxy_pearl")?;
+                        self.gummy_worm = reader.read_bool().field("gummy_worm")?;
+                        self.ambrosia = reader.read_bool().field("ambrosia")?;
                     }
                 }
             }
         }
 
         if self.version >= 182 {
-            self.defeated_ooa = reader.read_bool()?;
+            self.defeated_ooa = reader.read_bool().field("defeated_ooa")?;
         }
 
         if self.version >= 128 {
-            self.tax_money = reader.read_i32::<LE>()?;
+            self.tax_money = reader.read_i32::<LE>().field("tax_money")?;
         }
 
         if self.version >= 256 {
-            self.pve_deaths = reader.read_i32::<LE>()?;
-            self.pvp_deaths = reader.read_i32::<LE>()?;
+            self.pve_deaths = reader.read_i32::<LE>().field("pve_deaths")?;
+            self.pvp_deaths = reader.read_i32::<LE>().field("pvp_deaths")?;
         }
 
-        self.hair_color = reader.read_rgb()?;
-        self.skin_color = reader.read_rgb()?;
-        self.eye_color = reader.read_rgb()?;
-        self.shirt_color = reader.read_rgb()?;
-        self.undershirt_color = reader.read_rgb()?;
-        self.pants_color = reader.read_rgb()?;
-        self.shoe_color = reader.read_rgb()?;
+        self.hair_color = reader.read_rgb().field("hair_color")?;
+        self.skin_color = reader.read_rgb().field("skin_color")?;
+        self.eye_color = reader.read_rgb().field("eye_color")?;
+        self.shirt_color = reader.read_rgb().field("shirt_color")?;
+        self.undershirt_color = reader.read_rgb().field("undershirt_color")?;
+        self.pants_color = reader.read_rgb().field("pants_color")?;
+        self.shoe_color = reader.read_rgb().field("shoe_color")?;
 
         let has_prefix = self.version >= 36;
         let has_favourited = self.version >= 114;
 
-        self.loadouts[0].load(reader, item_meta, self.version, false, has_prefix)?;
+        self.loadouts[0]
+            .load(reader, item_meta, self.version, false, has_prefix)
+            .field_index("loadouts", 0)?;
 
         let inventory_count = if self.version >= 58 { 50 } else { 40 };
 
         for i in 0..inventory_count {
-            self.inventory[i].load(
-                reader,
-                item_meta,
-                true,
-                false,
-                true,
-                has_prefix,
-                has_favourited,
-            )?;
+            self.inventory[i]
+                .load_versioned(
+                    reader,
+                    item_meta,
+                    self.version,
+                    true,
+                    has_prefix,
+                    has_favourited,
+                )
+                .field_index("inventory", i)?;
         }
 
         for i in 0..COINS_COUNT {
-            self.coins[i].load(
-                reader,
-                item_meta,
-                true,
-                false,
-                true,
-                has_prefix,
-                has_favourited,
-            )?;
-        }
-
-        if self.version >= 15 {
-            for i in 0..AMMO_COUNT {
-                self.ammo[i].load(
+            self.coins[i]
+                .load_versioned(
                     reader,
                     item_meta,
-                    true,
-                    false,
+                    self.version,
                     true,
                     has_prefix,
                     has_favourited,
-                )?;
+                )
+                .field_index("coins", i)?;
+        }
+
+        if self.version >= 15 {
+            for i in 0..AMMO_COUNT {
+                self.ammo[i]
+                    .load_versioned(
+                        reader,
+                        item_meta,
+                        self.version,
+                        true,
+                        has_prefix,
+                        has_favourited,
+                    )
+                    .field_index("ammo", i)?;
             }
         }
 
@@ -469,26 +509,36 @@ impl Player {
             let start = if self.version >= 136 { 0 } else { 1 };
 
             for i in start..EQUIPMENT_COUNT {
-                self.equipment[i].load(reader, item_meta, true, false, false, true, false)?;
-                self.equipment_dyes[i].load(reader, item_meta, true, false, false, true, false)?;
+                self.equipment[i]
+                    .load(reader, item_meta, true, false, false, true, false)
+                    .field_index("equipment", i)?;
+                self.equipment_dyes[i]
+                    .load(reader, item_meta, true, false, false, true, false)
+                    .field_index("equipment_dyes", i)?;
             }
         }
 
         let bank_count = if self.version >= 58 { 40 } else { 20 };
 
         for i in 0..bank_count {
-            self.piggy_bank[i].load(reader, item_meta, true, false, true, has_prefix, false)?;
+            self.piggy_bank[i]
+                .load_versioned(reader, item_meta, self.version, true, has_prefix, false)
+                .field_index("piggy_bank", i)?;
         }
 
         if self.version >= 20 {
             for i in 0..bank_count {
-                self.safe[i].load(reader, item_meta, true, false, true, has_prefix, false)?;
+                self.safe[i]
+                    .load_versioned(reader, item_meta, self.version, true, has_prefix, false)
+                    .field_index("safe", i)?;
             }
         }
 
         if self.version >= 182 {
             for i in 0..bank_count {
-                self.defenders_forge[i].load(reader, item_meta, true, false, true, true, false)?;
+                self.defenders_forge[i]
+                    .load(reader, item_meta, true, false, true, true, false)
+                    .field_index("defenders_forge", i)?;
             }
         }
 
@@ -496,34 +546,17 @@ impl Player {
             let has_favourited = self.version >= 255;
 
             for i in 0..bank_count {
-                self.void_vault[i].load(
-                    reader,
-                    item_meta,
-                    true,
-                    false,
-                    true,
-                    true,
-                    has_favourited,
-                )?;
+                self.void_vault[i]
+                    .load(reader, item_meta, true, false, true, true, has_favourited)
+                    .field_index("void_vault", i)?;
             }
 
             if self.version >= 199 {
-                let bb = BoolByte::from(reader.read_u8()?);
+                let bb = BoolByte::from(reader.read_u8().field("void_vault_enabled")?);
                 self.void_vault_enabled = bb.get(0)?;
             }
         }
 
-        if self.version <= 57 {
-            for i in 0..4 {
-                self.coins[i] = self.inventory[i + 40].clone();
-                self.inventory[i + 40] = Item::default();
-            }
-            for i in 0..4 {
-                self.ammo[i] = self.inventory[i + 44].clone();
-                self.inventory[i + 44] = Item::default();
-            }
-        }
-
         if self.version >= 11 {
             let buff_count = if self.version >= 252 {
                 44
@@ -534,20 +567,32 @@ impl Player {
             };
 
             for i in 0..buff_count {
-                self.buffs[i].load(reader)?;
+                self.buffs[i].load(reader).field_index("buffs", i)?;
             }
         }
 
         self.spawnpoints.clear();
-        for _ in 0..SPAWNPOINT_LIMIT {
-            let x = reader.read_i32::<LE>()?;
+        for i in 0..SPAWNPOINT_LIMIT {
+            let x = reader
+                .read_i32::<LE>()
+                .field("x")
+                .field_index("spawnpoints", i)?;
             if x == -1 {
                 break;
             }
 
-            let y = reader.read_i32::<LE>()?;
-            let id = reader.read_i32::<LE>()?;
-            let name = reader.read_lpstring()?;
+            let y = reader
+                .read_i32::<LE>()
+                .field("y")
+                .field_index("spawnpoints", i)?;
+            let id = reader
+                .read_i32::<LE>()
+                .field("id")
+                .field_index("spawnpoints", i)?;
+            let name = reader
+                .read_lpstring()
+                .field("name")
+                .field_index("spawnpoints", i)?;
 
             let spawnpoint = Spawnpoint { x, y, id, name };
 
@@ -555,22 +600,22 @@ impl Player {
         }
 
         if self.version >= 16 {
-            self.locked_hotbar = reader.read_bool()?;
+            self.locked_hotbar = reader.read_bool().field("locked_hotbar")?;
         }
 
         if self.version >= 115 {
-            for i in self.hide_cellphone_info.iter_mut() {
-                *i = reader.read_bool()?;
+            for (i, hidden) in self.hide_cellphone_info.iter_mut().enumerate() {
+                *hidden = reader.read_bool().field_index("hide_cellphone_info", i)?;
             }
         }
 
         if self.version >= 98 {
-            self.angler_quests = reader.read_i32::<LE>()?;
+            self.angler_quests = reader.read_i32::<LE>().field("angler_quests")?;
         }
 
         if self.version >= 162 {
-            for b in self.dpad_bindings.iter_mut() {
-                *b = reader.read_i32::<LE>()?;
+            for (i, binding) in self.dpad_bindings.iter_mut().enumerate() {
+                *binding = reader.read_i32::<LE>().field_index("dpad_bindings", i)?;
             }
         }
 
@@ -586,7 +631,9 @@ impl Player {
             };
 
             for i in 0..status_count {
-                self.builder_accessory_status[i] = reader.read_i32::<LE>()?;
+                self.builder_accessory_status[i] = reader
+                    .read_i32::<LE>()
+                    .field_index("builder_accessory_status", i)?;
             }
 
             if self.version <= 209 {
@@ -600,56 +647,66 @@ impl Player {
         }
 
         if self.version >= 181 {
-            self.tavernkeep_quests = reader.read_i32::<LE>()?;
+            self.tavernkeep_quests = reader.read_i32::<LE>().field("tavernkeep_quests")?;
         }
 
         if self.version >= 200 {
-            self.dead = reader.read_bool()?;
+            self.dead = reader.read_bool().field("dead")?;
             if self.dead {
-                self.respawn_timer = reader.read_i32::<LE>()?.clamp(0, MAX_RESPAWN_TIME);
+                self.respawn_timer = reader
+                    .read_i32::<LE>()
+                    .field("respawn_timer")?
+                    .clamp(0, MAX_RESPAWN_TIME);
             }
         }
 
         if self.version >= 202 {
-            self.last_save = reader.read_i64::<LE>()?;
+            self.last_save = reader.read_i64::<LE>().field("last_save")?;
         } else {
             self.last_save = utils::current_save_time();
         }
 
         if self.version >= 206 {
-            self.golfer_score = reader.read_i32::<LE>()?;
+            self.golfer_score = reader.read_i32::<LE>().field("golfer_score")?;
         }
 
         if self.version >= 218 {
             if self.version >= 282 {
-                let _ = reader.read_bool();
+                self.share_research = reader.read_bool().field("share_research")?;
             }
-            let research_count = reader.read_i32::<LE>()?;
+            let research_count = reader.read_i32::<LE>().field("research")?;
 
             self.research.clear();
-            for _ in 0..research_count {
-                let research_item = ResearchItem::load_new(reader)?;
+            for i in 0..research_count {
+                let research_item =
+                    ResearchItem::load_new(reader).field_index("research", i as usize)?;
                 self.research.push(research_item);
             }
         }
 
         if self.version >= 214 {
-            let bb = BoolByte::from(reader.read_u8()?);
+            let bb = BoolByte::from(reader.read_u8().field("temporary_slots")?);
 
             for i in 0..TEMPORARY_SLOT_COUNT {
                 if bb.get(i as u8)? {
                     self.temporary_slots[i]
-                        .load(reader, item_meta, true, false, true, true, false)?;
+                        .load(reader, item_meta, true, false, true, true, false)
+                        .field_index("temporary_slots", i)?;
                 }
             }
         }
 
         if self.version >= 220 {
-            self.journey_powers.load(reader)?;
+            self.journey_powers.load(reader).field("journey_powers")?;
+
+            // The rest of the player is kept with the unrecognised power that ended the list
+            if self.journey_powers.has_raw_tail() {
+                return Ok(());
+            }
         }
 
         if self.version >= 253 {
-            let bb = BoolByte::from(reader.read_u8()?);
+            let bb = BoolByte::from(reader.read_u8().field("super_cart")?);
 
             self.super_cart = bb.get(0)?;
             self.super_cart_enabled = bb.get(1)?;
@@ -659,7 +716,7 @@ impl Player {
         }
 
         if self.version >= 262 {
-            self.current_loadout_index = reader.read_i32::<LE>()?;
+            self.current_loadout_index = reader.read_i32::<LE>().field("current_loadout_index")?;
             self.current_loadout_index = self
                 .current_loadout_index
                 .clamp(0, (LOADOUT_COUNT - 1) as i32);
@@ -670,68 +727,149 @@ impl Player {
 
             for i in 0..LOADOUT_COUNT {
                 if i == self.current_loadout_index as usize {
-                    Loadout::skip(reader, self.version, true, true)?;
-                    Loadout::skip_visuals(reader, self.version, false)?;
+                    Loadout::skip(reader, self.version, true, true).field_index("loadouts", i)?;
+                    Loadout::skip_visuals(reader, self.version, false)
+                        .field_index("loadouts", i)?;
                 } else {
-                    self.loadouts[i].load(reader, item_meta, self.version, true, true)?;
-                    self.loadouts[i].load_visuals(reader, self.version, false)?;
+                    self.loadouts[i]
+                        .load(reader, item_meta, self.version, true, true)
+                        .field_index("loadouts", i)?;
+                    self.loadouts[i]
+                        .load_visuals(reader, self.version, false)
+                        .field_index("loadouts", i)?;
                 }
             }
         }
 
         if self.version >= 280 {
-            self.voice_variant = reader.read_u8()?;
+            self.voice_variant = reader.read_u8().field("voice_variant")?;
         } else {
             self.voice_variant = if self.male { 1 } else { 2 }
         }
 
         if self.version >= 281 {
-            self.voice_pitch_offset = reader.read_f32::<LE>()?;
+            self.voice_pitch_offset = reader.read_f32::<LE>().field("voice_pitch_offset")?;
         }
 
         if self.version >= 300 {
-            let count = reader.read_i32::<LE>()? as usize;
+            let count = reader.read_i32::<LE>().field("pending_refunds")? as usize;
             self.pending_refunds.reserve(count);
-            for _ in 0..count {
+            for i in 0..count {
                 let mut item = Item::default();
-                item.load(reader, item_meta, true, false, true, true, false)?;
+                item.load(reader, item_meta, true, false, true, true, false)
+                    .field_index("pending_refunds", i)?;
                 self.pending_refunds.push(item);
             }
         }
 
         if self.version >= 310 {
-            let count = reader.read_i32::<LE>()? as usize;
+            let count = reader.read_i32::<LE>().field("one_time_dialogues_seen")? as usize;
             self.one_time_dialogues_seen.reserve(count);
-            for _ in 0..count {
-                self.one_time_dialogues_seen.push(reader.read_lpstring()?);
+            for i in 0..count {
+                let dialogue = reader
+                    .read_lpstring()
+                    .field_index("one_time_dialogues_seen", i)?;
+                self.one_time_dialogues_seen.push(dialogue);
             }
         }
 
         Ok(())
     }
 
-    pub fn load(&mut self, item_meta: &[ItemMeta], filepath: &Path) -> anyhow::Result<()> {
+    fn load_from_format(
+        &mut self,
+        item_meta: &MetaRegistry<ItemMeta>,
+        reader: &mut dyn Read,
+        format: PlayerFormat,
+    ) -> anyhow::Result<()> {
+        if format == PlayerFormat::Decrypted {
+            return self.load_tracked(item_meta, reader);
+        }
+
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        // Mobile players are detected even if they weren't expected
+        self.is_mobile = try_truncate_mobile_data(&mut data) || format == PlayerFormat::Mobile;
+
+        let buf = decrypt_from_reader(Cursor::new(data))?;
+        self.load_tracked(item_meta, &mut Cursor::new(buf))
+    }
+
+    /// Loads a decrypted player, turning errors from within a field into
+    /// `PlayerError::Parse` so they say where the file went wrong.
+    fn load_tracked(
+        &mut self,
+        item_meta: &MetaRegistry<ItemMeta>,
+        reader: &mut dyn Read,
+    ) -> anyhow::Result<()> {
+        let mut reader = PositionReader::new(reader);
+
+        match self.load_from_reader(item_meta, &mut reader) {
+            Ok(()) => Ok(()),
+            Err(error) => {
+                let (field, source) = take_field_path(error);
+                if field.is_empty() {
+                    return Err(source);
+                }
+
+                Err(PlayerError::Parse {
+                    field,
+                    offset: reader.position(),
+                    version: self.version,
+                    source: source.into(),
+                }
+                .into())
+            }
+        }
+    }
+
+    /// Reads a player in the given format.
+    ///
+    /// Unlike `load`, this doesn't look for a tModLoader `.tplr` file.
+    pub fn from_reader(
+        item_meta: &MetaRegistry<ItemMeta>,
+        reader: &mut dyn Read,
+        format: PlayerFormat,
+    ) -> anyhow::Result<Self> {
+        let mut player = Self::default();
+        player.load_from_format(item_meta, reader, format)?;
+        Ok(player)
+    }
+
+    pub fn from_bytes(
+        item_meta: &MetaRegistry<ItemMeta>,
+        bytes: &[u8],
+        format: PlayerFormat,
+    ) -> anyhow::Result<Self> {
+        Self::from_reader(item_meta, &mut Cursor::new(bytes), format)
+    }
+
+    pub fn load(
+        &mut self,
+        item_meta: &MetaRegistry<ItemMeta>,
+        filepath: &Path,
+    ) -> anyhow::Result<()> {
         let mut file = open_file(filepath)?;
-        let mut data = Vec::with_capacity(file.metadata().map(|m| m.len() as usize).unwrap_or(0));
-        file.read_to_end(&mut data)?;
-        self.is_mobile = try_truncate_mobile_data(&mut data);
+        self.load_from_format(item_meta, &mut file, PlayerFormat::Encrypted)?;
 
-        let mut reader = Cursor::new(data);
-        let buf = decrypt_from_reader(&mut reader)?;
-        let mut reader = Cursor::new(buf);
-        self.load_from_reader(item_meta, &mut reader)
+        self.load_mod_data(&filepath.with_extension("tplr"))
     }
 
     pub fn load_decrypted(
         &mut self,
-        item_meta: &[ItemMeta],
+        item_meta: &MetaRegistry<ItemMeta>,
         filepath: &Path,
     ) -> anyhow::Result<()> {
         let mut file = open_file(filepath)?;
-        self.load_from_reader(item_meta, &mut file)
+        self.load_from_format(item_meta, &mut file, PlayerFormat::Decrypted)
     }
 
-    fn save_to_writer(&self, item_meta: &[ItemMeta], writer: &mut dyn Write) -> anyhow::Result<()> {
+    fn save_to_writer(
+        &self,
+        item_meta: &MetaRegistry<ItemMeta>,
+        writer: &mut dyn Write,
+    ) -> anyhow::Result<()> {
         writer.write_i32::<LE>(self.version)?;
 
         if self.version >= 135 {
@@ -824,7 +962,7 @@ impl Player {
             writer.write_i32::<LE>(self.tax_money)?;
         }
 
-        if self.version >= 257 {
+        if self.version >= 256 {
             writer.write_i32::<LE>(self.pve_deaths)?;
             writer.write_i32::<LE>(self.pvp_deaths)?;
         }
@@ -851,11 +989,10 @@ impl Player {
         let inventory_count = if self.version >= 58 { 50 } else { 40 };
 
         for i in 0..inventory_count {
-            self.inventory[i].save(
+            self.inventory[i].save_versioned(
                 writer,
                 item_meta,
-                true,
-                false,
+                self.version,
                 true,
                 has_prefix,
                 has_favourited,
@@ -863,11 +1000,10 @@ impl Player {
         }
 
         for i in 0..COINS_COUNT {
-            self.coins[i].save(
+            self.coins[i].save_versioned(
                 writer,
                 item_meta,
-                true,
-                false,
+                self.version,
                 true,
                 has_prefix,
                 has_favourited,
@@ -876,11 +1012,10 @@ impl Player {
 
         if self.version >= 15 {
             for i in 0..AMMO_COUNT {
-                self.ammo[i].save(
+                self.ammo[i].save_versioned(
                     writer,
                     item_meta,
-                    true,
-                    false,
+                    self.version,
                     true,
                     has_prefix,
                     has_favourited,
@@ -900,12 +1035,26 @@ impl Player {
         let bank_count = if self.version >= 58 { 40 } else { 20 };
 
         for i in 0..bank_count {
-            self.piggy_bank[i].save(writer, item_meta, true, false, true, has_prefix, false)?;
+            self.piggy_bank[i].save_versioned(
+                writer,
+                item_meta,
+                self.version,
+                true,
+                has_prefix,
+                false,
+            )?;
         }
 
         if self.version >= 20 {
             for i in 0..bank_count {
-                self.safe[i].save(writer, item_meta, true, false, true, has_prefix, false)?;
+                self.safe[i].save_versioned(
+                    writer,
+                    item_meta,
+                    self.version,
+                    true,
+                    has_prefix,
+                    false,
+                )?;
             }
         }
 
@@ -997,7 +1146,7 @@ impl Player {
             writer.write_i32::<LE>(self.tavernkeep_quests)?;
         }
 
-        if self.version >= 220 {
+        if self.version >= 200 {
             writer.write_bool(self.dead)?;
             if self.dead {
                 writer.write_i32::<LE>(self.respawn_timer)?;
@@ -1014,7 +1163,7 @@ impl Player {
 
         if self.version >= 218 {
             if self.version >= 282 {
-                writer.write_bool(false)?;
+                writer.write_bool(self.share_research)?;
             }
             writer.write_i32::<LE>(self.research.len() as i32)?;
 
@@ -1045,6 +1194,10 @@ impl Player {
 
         if self.version >= 220 {
             self.journey_powers.save(writer, &self.difficulty)?;
+
+            if self.journey_powers.has_raw_tail() {
+                return Ok(());
+            }
         }
 
         if self.version >= 253 {
@@ -1098,26 +1251,123 @@ impl Player {
         Ok(())
     }
 
-    pub fn save(&self, item_meta: &[ItemMeta], filepath: &Path) -> anyhow::Result<()> {
-        let mut file = create_file(filepath)?;
-        let mut buf = Vec::new();
-        self.save_to_writer(item_meta, &mut buf)?;
+    /// The format the player was loaded from, and will be saved in by `save`.
+    pub fn format(&self) -> PlayerFormat {
         if self.is_mobile {
-            let mut out = Vec::with_capacity(buf.len());
-            encrypt_to_writer(&mut out, &buf)?;
-            if self.is_mobile {
-                align_mobile_data(&mut out);
-            }
-            file.write_all(&out)?;
+            PlayerFormat::Mobile
         } else {
-            encrypt_to_writer(&mut file, &buf)?;
+            PlayerFormat::Encrypted
+        }
+    }
+
+    /// Writes the player in the given format.
+    ///
+    /// Unlike `save`, this doesn't write a tModLoader `.tplr` file.
+    pub fn to_writer(
+        &self,
+        item_meta: &MetaRegistry<ItemMeta>,
+        writer: &mut dyn Write,
+        format: PlayerFormat,
+    ) -> anyhow::Result<()> {
+        if format == PlayerFormat::Decrypted {
+            return self.save_to_writer(item_meta, writer);
+        }
+
+        let mut buf = Vec::new();
+        self.save_to_writer(item_meta, &mut buf)?;
+
+        let mut out = Vec::with_capacity(buf.len());
+        encrypt_to_writer(&mut out, &buf)?;
+        if format == PlayerFormat::Mobile {
+            align_mobile_data(&mut out);
         }
+
+        writer.write_all(&out)?;
         Ok(())
     }
 
-    pub fn save_decrypted(&self, item_meta: &[ItemMeta], filepath: &Path) -> anyhow::Result<()> {
+    pub fn to_bytes(
+        &self,
+        item_meta: &MetaRegistry<ItemMeta>,
+        format: PlayerFormat,
+    ) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.to_writer(item_meta, &mut bytes, format)?;
+        Ok(bytes)
+    }
+
+    pub fn save(&self, item_meta: &MetaRegistry<ItemMeta>, filepath: &Path) -> anyhow::Result<()> {
+        let mut file = create_file(filepath)?;
+        self.to_writer(item_meta, &mut file, self.format())?;
+
+        self.save_mod_data(&filepath.with_extension("tplr"))
+    }
+
+    /// Loads the tModLoader `.tplr` file beside a player, if there is one, placing
+    /// its items into their slots.
+    ///
+    /// This is done automatically by `load`.
+    pub fn load_mod_data(&mut self, filepath: &Path) -> anyhow::Result<()> {
+        self.clear_mod_items();
+        self.mod_data = None;
+
+        if !filepath.exists() {
+            return Ok(());
+        }
+
+        let mut mod_data = ModPlayer::load(filepath)?;
+        mod_data.take_items(self);
+        self.mod_data = Some(mod_data);
+
+        Ok(())
+    }
+
+    /// Saves the tModLoader `.tplr` file, if the player was loaded with one, so
+    /// that it matches the edited player.
+    ///
+    /// This is done automatically by `save`.
+    pub fn save_mod_data(&self, filepath: &Path) -> anyhow::Result<()> {
+        match &self.mod_data {
+            Some(mod_data) => mod_data.with_items(self).save(filepath),
+            None => Ok(()),
+        }
+    }
+
+    fn clear_mod_items(&mut self) {
+        let loadout_items = self.loadouts.iter_mut().flat_map(|l| {
+            l.armor
+                .iter_mut()
+                .chain(l.vanity_armor.iter_mut())
+                .chain(l.armor_dyes.iter_mut())
+                .chain(l.accessories.iter_mut())
+                .chain(l.vanity_accessories.iter_mut())
+                .chain(l.accessory_dyes.iter_mut())
+        });
+
+        for item in self
+            .inventory
+            .iter_mut()
+            .chain(self.coins.iter_mut())
+            .chain(self.ammo.iter_mut())
+            .chain(self.equipment.iter_mut())
+            .chain(self.equipment_dyes.iter_mut())
+            .chain(self.piggy_bank.iter_mut())
+            .chain(self.safe.iter_mut())
+            .chain(self.defenders_forge.iter_mut())
+            .chain(self.void_vault.iter_mut())
+            .chain(loadout_items)
+        {
+            item.mod_item = None;
+        }
+    }
+
+    pub fn save_decrypted(
+        &self,
+        item_meta: &MetaRegistry<ItemMeta>,
+        filepath: &Path,
+    ) -> anyhow::Result<()> {
         let mut file = create_file(filepath)?;
-        self.save_to_writer(item_meta, &mut file)
+        self.to_writer(item_meta, &mut file, PlayerFormat::Decrypted)
     }
 
     pub fn decrypt_file(original_filepath: &Path, decrypted_filepath: &Path) -> anyhow::Result<()> {
@@ -2,4 +2,4 @@ mod prefix_data;
 mod prefix_meta;
 
 pub use prefix_data::Prefix;
-pub use prefix_meta::PrefixMeta;
+pub use prefix_meta::{PrefixCategory, PrefixMeta};
@@ -1,12 +1,54 @@
-use crate::{meta::Meta, SharedString};
+use crate::{meta::Meta, ItemMeta, ItemType, SharedString};
 
+/// The Terrarian is the only item that can be Legendary2.
+const TERRARIAN_ID: i32 = 3389;
+const LEGENDARY_TERRARIAN_ID: u8 = 84;
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serialize", derive(serde_repr::Serialize_repr))]
+#[cfg_attr(feature = "deserialize", derive(serde_repr::Deserialize_repr))]
+pub enum PrefixCategory {
+    /// Any weapon.
+    Universal,
+    /// Speed prefixes, which any weapon can have.
+    Common,
+    Melee,
+    Ranged,
+    /// Magic and summon weapons.
+    Magic,
+    Accessory,
+}
+
+/// Multipliers are 1 for no change, so `use_time: 0.9` is 10% faster.
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(
+    feature = "serialize",
+    serde_with::skip_serializing_none,
+    derive(serde::Serialize)
+)]
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
 pub struct PrefixMeta {
     pub id: u8,
     pub name: SharedString,
     pub internal_name: SharedString,
+    /// `None` for the empty prefix.
+    pub category: Option<PrefixCategory>,
+    /// Multiplies a weapon's damage, or the player's damage for accessories.
+    pub damage: Option<f32>,
+    pub use_time: Option<f32>,
+    /// Added to the critical strike chance, in percent.
+    pub crit: Option<i32>,
+    pub knockback: Option<f32>,
+    pub mana_cost: Option<f32>,
+    pub size: Option<f32>,
+    /// Multiplies the speed of the projectiles a weapon shoots.
+    pub velocity: Option<f32>,
+    pub defense: Option<i32>,
+    /// Added to the player's max mana.
+    pub mana: Option<i32>,
+    pub move_speed: Option<f32>,
+    pub melee_speed: Option<f32>,
 }
 
 impl Meta for PrefixMeta {
@@ -24,3 +66,39 @@ impl Meta for PrefixMeta {
         self.internal_name.clone()
     }
 }
+
+impl PrefixMeta {
+    /// Whether the game could give this prefix to the item when reforging it.
+    ///
+    /// Melee prefixes, which include those that change size, are only given to weapons that
+    /// are swung. Yoyos, spears, flails and the like only get universal and common ones.
+    pub fn is_valid_for(&self, item: &ItemMeta) -> bool {
+        if !item.can_have_prefix() {
+            return false;
+        }
+        if self.id == LEGENDARY_TERRARIAN_ID {
+            return item.id == TERRARIAN_ID;
+        }
+
+        let Some(item_type) = item.item_type else {
+            return false;
+        };
+        let is_weapon = matches!(
+            item_type,
+            ItemType::Melee | ItemType::Ranged | ItemType::Magic | ItemType::Summon
+        );
+
+        match self.category {
+            None => false,
+            Some(PrefixCategory::Universal | PrefixCategory::Common) => is_weapon,
+            Some(PrefixCategory::Melee) => {
+                item_type == ItemType::Melee && !item.is_projectile_melee.is_some_and(|p| p)
+            }
+            Some(PrefixCategory::Ranged) => item_type == ItemType::Ranged,
+            Some(PrefixCategory::Magic) => {
+                matches!(item_type, ItemType::Magic | ItemType::Summon)
+            }
+            Some(PrefixCategory::Accessory) => item_type == ItemType::Accessory,
+        }
+    }
+}
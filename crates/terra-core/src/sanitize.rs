@@ -0,0 +1,210 @@
+use std::{collections::HashSet, fmt::Display};
+
+use crate::{
+    Buff, ContainerKind, Item, MetaBundle, Player, SlotRef, COIN_IDS, LOADOUT_COUNT, MAX_LIFE,
+    MAX_MANA,
+};
+
+/// Something `Player::sanitize` fixed.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum Repair {
+    /// The item's id doesn't exist.
+    RemovedItem {
+        slot: String,
+        id: i32,
+    },
+    /// The prefix doesn't exist, or it can't go on the item.
+    RemovedPrefix {
+        slot: String,
+        prefix: u8,
+    },
+    ClampedStack {
+        slot: String,
+        id: i32,
+        from: i32,
+        to: i32,
+    },
+    /// A duplicate accessory, or an item in a coin or ammo slot it doesn't belong in, was
+    /// moved into the inventory.
+    MovedItem {
+        from: String,
+        to: String,
+        id: i32,
+    },
+    /// The buff doesn't exist, or its duration was negative.
+    RemovedBuff {
+        slot: String,
+        id: i32,
+    },
+    ChangedField {
+        field: String,
+        from: i32,
+        to: i32,
+    },
+}
+
+impl Display for Repair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RemovedItem { slot, id } => {
+                write!(f, "Removed unknown item {id} from {slot}")
+            }
+            Self::RemovedPrefix { slot, prefix } => {
+                write!(f, "Removed prefix {prefix} from the item in {slot}")
+            }
+            Self::ClampedStack { slot, id, from, to } => write!(
+                f,
+                "Changed the stack of item {id} in {slot} from {from} to {to}"
+            ),
+            Self::MovedItem { from, to, id } => write!(f, "Moved item {id} from {from} to {to}"),
+            Self::RemovedBuff { slot, id } => write!(f, "Removed buff {id} from {slot}"),
+            Self::ChangedField { field, from, to } => {
+                write!(f, "Changed {field} from {from} to {to}")
+            }
+        }
+    }
+}
+
+struct Sanitizer<'a> {
+    meta: &'a MetaBundle,
+    repairs: Vec<Repair>,
+}
+
+impl Sanitizer<'_> {
+    fn field(&mut self, field: &str, value: &mut i32, to: i32) {
+        if *value != to {
+            self.repairs.push(Repair::ChangedField {
+                field: field.to_owned(),
+                from: *value,
+                to,
+            });
+            *value = to;
+        }
+    }
+
+    fn item(&mut self, slot: impl Fn() -> String, item: &mut Item) {
+        if item.id == 0 {
+            return;
+        }
+
+        let Some(meta) = self.meta.items.get(item.id) else {
+            self.repairs.push(Repair::RemovedItem {
+                slot: slot(),
+                id: item.id,
+            });
+            *item = Item::default();
+            return;
+        };
+
+        let prefix_valid = self
+            .meta
+            .prefixes
+            .get(item.prefix.id)
+            .is_some_and(|prefix| prefix.is_valid_for(meta));
+        if item.prefix.id != 0 && !prefix_valid {
+            self.repairs.push(Repair::RemovedPrefix {
+                slot: slot(),
+                prefix: item.prefix.id,
+            });
+            item.prefix.id = 0;
+        }
+
+        let stack = item.stack.clamp(1, meta.effective_max_stack().max(1));
+        if stack != item.stack {
+            self.repairs.push(Repair::ClampedStack {
+                slot: slot(),
+                id: item.id,
+                from: item.stack,
+                to: stack,
+            });
+            item.stack = stack;
+        }
+    }
+
+    fn buffs(&mut self, buffs: &mut [Buff]) {
+        for (i, buff) in buffs.iter_mut().enumerate() {
+            if buff.id != 0 && (buff.time < 0 || self.meta.buffs.get(buff.id).is_none()) {
+                self.repairs.push(Repair::RemovedBuff {
+                    slot: format!("buffs[{i}]"),
+                    id: buff.id,
+                });
+                *buff = Buff::default();
+            }
+        }
+    }
+}
+
+impl Player {
+    /// Fixes the problems `validate` finds that have a safe fix, returning what was changed.
+    ///
+    /// Unknown items and buffs are removed, stacks are clamped, and invalid prefixes are
+    /// stripped. Duplicate accessories and items in the wrong coin or ammo slot are moved to
+    /// the first empty inventory slot, or left where they are if the inventory is full.
+    pub fn sanitize(&mut self, meta: &MetaBundle) -> Vec<Repair> {
+        let mut sanitizer = Sanitizer {
+            meta,
+            repairs: Vec::new(),
+        };
+
+        if !(0..LOADOUT_COUNT as i32).contains(&self.current_loadout_index) {
+            sanitizer.field("current_loadout_index", &mut self.current_loadout_index, 0);
+        }
+        let max_life = self.max_life.min(MAX_LIFE);
+        sanitizer.field("max_life", &mut self.max_life, max_life);
+        let max_mana = self.max_mana.min(MAX_MANA);
+        sanitizer.field("max_mana", &mut self.max_mana, max_mana);
+
+        for container in ContainerKind::iter() {
+            let Some(items) = container.items_mut(self) else {
+                continue;
+            };
+            for (i, item) in items.iter_mut().enumerate() {
+                sanitizer.item(|| SlotRef::new(container, i).to_string(), item);
+            }
+        }
+        sanitizer.buffs(&mut self.buffs);
+
+        // Everything else is moved once the items themselves are valid
+        let mut equipped = HashSet::new();
+        let misplaced: Vec<SlotRef> = self
+            .iter_slots()
+            .filter(|(slot, item)| {
+                item.id != 0
+                    && match slot.container {
+                        ContainerKind::Accessories(loadout)
+                        | ContainerKind::VanityAccessories(loadout) => {
+                            !equipped.insert((loadout, item.id))
+                        }
+                        ContainerKind::Coins => !COIN_IDS.contains(&item.id),
+                        ContainerKind::Ammo => {
+                            !meta.items.get(item.id).is_some_and(|m| m.fits_ammo_slot())
+                        }
+                        _ => false,
+                    }
+            })
+            .map(|(slot, _)| slot)
+            .collect();
+
+        let inventory_count = if self.version >= 58 { 50 } else { 40 };
+        for from in misplaced {
+            let Some(to) = self.inventory[..inventory_count]
+                .iter()
+                .position(|i| i.id == 0)
+            else {
+                break;
+            };
+
+            let item = from.get_mut(self).map(std::mem::take).unwrap_or_default();
+            sanitizer.repairs.push(Repair::MovedItem {
+                from: from.to_string(),
+                to: format!("inventory[{to}]"),
+                id: item.id,
+            });
+            self.inventory[to] = item;
+        }
+
+        sanitizer.repairs
+    }
+}
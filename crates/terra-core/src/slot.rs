@@ -0,0 +1,255 @@
+use std::fmt::Display;
+
+use crate::{Item, Player, LOADOUT_COUNT};
+
+#[derive(thiserror::Error, Debug)]
+pub enum SlotError {
+    #[error("The slot {0} doesn't exist.")]
+    InvalidSlot(SlotRef),
+}
+
+/// An array of item slots on a player. Loadout containers are by loadout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+#[cfg_attr(
+    any(feature = "serialize", feature = "deserialize"),
+    serde(rename_all = "snake_case")
+)]
+pub enum ContainerKind {
+    Inventory,
+    Coins,
+    Ammo,
+    PiggyBank,
+    Safe,
+    DefendersForge,
+    VoidVault,
+    Equipment,
+    EquipmentDyes,
+    Armor(usize),
+    VanityArmor(usize),
+    ArmorDyes(usize),
+    Accessories(usize),
+    VanityAccessories(usize),
+    AccessoryDyes(usize),
+    TemporarySlots,
+    PendingRefunds,
+}
+
+impl ContainerKind {
+    /// Every container, with each loadout's containers after the equipment.
+    pub fn iter() -> impl Iterator<Item = Self> {
+        let loadouts = (0..LOADOUT_COUNT).flat_map(|loadout| {
+            [
+                Self::Armor(loadout),
+                Self::VanityArmor(loadout),
+                Self::ArmorDyes(loadout),
+                Self::Accessories(loadout),
+                Self::VanityAccessories(loadout),
+                Self::AccessoryDyes(loadout),
+            ]
+        });
+
+        [
+            Self::Inventory,
+            Self::Coins,
+            Self::Ammo,
+            Self::PiggyBank,
+            Self::Safe,
+            Self::DefendersForge,
+            Self::VoidVault,
+            Self::Equipment,
+            Self::EquipmentDyes,
+        ]
+        .into_iter()
+        .chain(loadouts)
+        .chain([Self::TemporarySlots, Self::PendingRefunds])
+    }
+
+    /// The loadout the container belongs to, if it's part of one.
+    pub fn loadout(&self) -> Option<usize> {
+        match *self {
+            Self::Armor(loadout)
+            | Self::VanityArmor(loadout)
+            | Self::ArmorDyes(loadout)
+            | Self::Accessories(loadout)
+            | Self::VanityAccessories(loadout)
+            | Self::AccessoryDyes(loadout) => Some(loadout),
+            _ => None,
+        }
+    }
+
+    /// The container's slots, or `None` if its loadout doesn't exist.
+    pub fn items<'a>(&self, player: &'a Player) -> Option<&'a [Item]> {
+        if self
+            .loadout()
+            .is_some_and(|loadout| loadout >= LOADOUT_COUNT)
+        {
+            return None;
+        }
+
+        Some(match *self {
+            Self::Inventory => &player.inventory,
+            Self::Coins => &player.coins,
+            Self::Ammo => &player.ammo,
+            Self::PiggyBank => &player.piggy_bank,
+            Self::Safe => &player.safe,
+            Self::DefendersForge => &player.defenders_forge,
+            Self::VoidVault => &player.void_vault,
+            Self::Equipment => &player.equipment,
+            Self::EquipmentDyes => &player.equipment_dyes,
+            Self::Armor(loadout) => &player.loadouts[loadout].armor,
+            Self::VanityArmor(loadout) => &player.loadouts[loadout].vanity_armor,
+            Self::ArmorDyes(loadout) => &player.loadouts[loadout].armor_dyes,
+            Self::Accessories(loadout) => &player.loadouts[loadout].accessories,
+            Self::VanityAccessories(loadout) => &player.loadouts[loadout].vanity_accessories,
+            Self::AccessoryDyes(loadout) => &player.loadouts[loadout].accessory_dyes,
+            Self::TemporarySlots => &player.temporary_slots,
+            Self::PendingRefunds => &player.pending_refunds,
+        })
+    }
+
+    pub fn items_mut<'a>(&self, player: &'a mut Player) -> Option<&'a mut [Item]> {
+        if self
+            .loadout()
+            .is_some_and(|loadout| loadout >= LOADOUT_COUNT)
+        {
+            return None;
+        }
+
+        Some(match *self {
+            Self::Inventory => &mut player.inventory,
+            Self::Coins => &mut player.coins,
+            Self::Ammo => &mut player.ammo,
+            Self::PiggyBank => &mut player.piggy_bank,
+            Self::Safe => &mut player.safe,
+            Self::DefendersForge => &mut player.defenders_forge,
+            Self::VoidVault => &mut player.void_vault,
+            Self::Equipment => &mut player.equipment,
+            Self::EquipmentDyes => &mut player.equipment_dyes,
+            Self::Armor(loadout) => &mut player.loadouts[loadout].armor,
+            Self::VanityArmor(loadout) => &mut player.loadouts[loadout].vanity_armor,
+            Self::ArmorDyes(loadout) => &mut player.loadouts[loadout].armor_dyes,
+            Self::Accessories(loadout) => &mut player.loadouts[loadout].accessories,
+            Self::VanityAccessories(loadout) => &mut player.loadouts[loadout].vanity_accessories,
+            Self::AccessoryDyes(loadout) => &mut player.loadouts[loadout].accessory_dyes,
+            Self::TemporarySlots => &mut player.temporary_slots,
+            Self::PendingRefunds => &mut player.pending_refunds,
+        })
+    }
+}
+
+impl Display for ContainerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Inventory => write!(f, "inventory"),
+            Self::Coins => write!(f, "coins"),
+            Self::Ammo => write!(f, "ammo"),
+            Self::PiggyBank => write!(f, "piggy_bank"),
+            Self::Safe => write!(f, "safe"),
+            Self::DefendersForge => write!(f, "defenders_forge"),
+            Self::VoidVault => write!(f, "void_vault"),
+            Self::Equipment => write!(f, "equipment"),
+            Self::EquipmentDyes => write!(f, "equipment_dyes"),
+            Self::Armor(loadout) => write!(f, "loadouts[{loadout}].armor"),
+            Self::VanityArmor(loadout) => write!(f, "loadouts[{loadout}].vanity_armor"),
+            Self::ArmorDyes(loadout) => write!(f, "loadouts[{loadout}].armor_dyes"),
+            Self::Accessories(loadout) => write!(f, "loadouts[{loadout}].accessories"),
+            Self::VanityAccessories(loadout) => {
+                write!(f, "loadouts[{loadout}].vanity_accessories")
+            }
+            Self::AccessoryDyes(loadout) => write!(f, "loadouts[{loadout}].accessory_dyes"),
+            Self::TemporarySlots => write!(f, "temporary_slots"),
+            Self::PendingRefunds => write!(f, "pending_refunds"),
+        }
+    }
+}
+
+/// A single item slot on a player, like `loadouts[1].accessories[3]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct SlotRef {
+    pub container: ContainerKind,
+    pub index: usize,
+}
+
+impl SlotRef {
+    pub fn new(container: ContainerKind, index: usize) -> Self {
+        Self { container, index }
+    }
+
+    /// The item in the slot, or `None` if the slot doesn't exist.
+    pub fn get<'a>(&self, player: &'a Player) -> Option<&'a Item> {
+        self.container.items(player)?.get(self.index)
+    }
+
+    pub fn get_mut<'a>(&self, player: &'a mut Player) -> Option<&'a mut Item> {
+        self.container.items_mut(player)?.get_mut(self.index)
+    }
+
+    /// Swaps the items in two slots, which can be in different containers.
+    pub fn swap(&self, other: &SlotRef, player: &mut Player) -> Result<(), SlotError> {
+        for slot in [self, other] {
+            if slot.get(player).is_none() {
+                return Err(SlotError::InvalidSlot(*slot));
+            }
+        }
+        if self == other {
+            return Ok(());
+        }
+
+        let item = std::mem::take(self.get_mut(player).unwrap());
+        let item = std::mem::replace(other.get_mut(player).unwrap(), item);
+        *self.get_mut(player).unwrap() = item;
+        Ok(())
+    }
+}
+
+impl Display for SlotRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}[{}]", self.container, self.index)
+    }
+}
+
+/// An item found by `Player::find_items`, and where it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct FoundItem {
+    pub slot: SlotRef,
+    pub id: i32,
+    pub stack: i32,
+    pub prefix: u8,
+}
+
+impl Player {
+    /// Every item slot on the player, empty or not, in `ContainerKind::iter`'s order.
+    ///
+    /// This includes slots the player's version doesn't save, like the last 10 inventory
+    /// slots before version 58.
+    pub fn iter_slots(&self) -> impl Iterator<Item = (SlotRef, &Item)> {
+        ContainerKind::iter().flat_map(move |container| {
+            container
+                .items(self)
+                .into_iter()
+                .flatten()
+                .enumerate()
+                .map(move |(index, item)| (SlotRef::new(container, index), item))
+        })
+    }
+
+    /// Every non-empty slot holding an item that matches, in `iter_slots`'s order. Unlike
+    /// `has_item`, this includes the temporary slots and pending refunds.
+    pub fn find_items(&self, mut predicate: impl FnMut(&Item) -> bool) -> Vec<FoundItem> {
+        self.iter_slots()
+            .filter(|(_, item)| item.id != 0 && predicate(item))
+            .map(|(slot, item)| FoundItem {
+                slot,
+                id: item.id,
+                stack: item.stack,
+                prefix: item.prefix.id,
+            })
+            .collect()
+    }
+}
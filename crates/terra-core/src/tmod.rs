@@ -0,0 +1,5 @@
+mod mod_player;
+mod tag;
+
+pub use mod_player::{ModItem, ModPlayer};
+pub use tag::{Tag, TagCompound, TagError};
@@ -0,0 +1,268 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+use crate::{
+    Item, Player, Tag, TagCompound, ACCESSORY_COUNT, AMMO_COUNT, ARMOR_COUNT, BANK_COUNT,
+    COINS_COUNT, EQUIPMENT_COUNT, INVENTORY_COUNT,
+};
+
+/// The `mod` name tModLoader uses for vanilla items that also carry mod data.
+const VANILLA_MOD: &str = "Terraria";
+
+/// Keys of the `.tplr` lists which hold items, along with the slot they were in.
+const ITEM_LIST_KEYS: [&str; 9] = [
+    "armor",
+    "dye",
+    "inventory",
+    "miscEquips",
+    "miscDyes",
+    "bank",
+    "bank2",
+    "bank3",
+    "bank4",
+];
+
+/// An item saved in a `.tplr` file, which terra-rs can't display.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ModItem {
+    pub tag: TagCompound,
+}
+
+impl ModItem {
+    pub fn mod_name(&self) -> &str {
+        self.tag.get_str("mod").unwrap_or_default()
+    }
+
+    pub fn name(&self) -> &str {
+        self.tag.get_str("name").unwrap_or_default()
+    }
+
+    /// Vanilla items are only saved in the `.tplr` when a mod has attached data to them,
+    /// the item itself is still in the `.plr`.
+    pub fn is_vanilla(&self) -> bool {
+        self.mod_name() == VANILLA_MOD
+    }
+
+    pub fn vanilla_id(&self) -> Option<i32> {
+        self.tag.get_i64("id").map(|id| id as i32)
+    }
+
+    pub fn stack(&self) -> i32 {
+        self.tag.get_i64("stack").map(|s| s as i32).unwrap_or(1)
+    }
+
+    /// `Mod/Name`, looking through tModLoader's placeholder for items from unloaded mods.
+    pub fn display_name(&self) -> String {
+        if self.mod_name() == "ModLoader" && self.name() == "UnloadedItem" {
+            if let Some(data) = self.tag.get("data").and_then(Tag::as_compound) {
+                return format!(
+                    "{}/{}",
+                    data.get_str("mod").unwrap_or_default(),
+                    data.get_str("name").unwrap_or_default()
+                );
+            }
+        }
+
+        format!("{}/{}", self.mod_name(), self.name())
+    }
+
+    /// Brings a vanilla item's tag in line with any edits made to it.
+    fn sync_vanilla(&mut self, item: &Item) {
+        if item.stack > 1 {
+            self.tag.set("stack", Tag::Int(item.stack));
+        } else {
+            self.tag.remove("stack");
+        }
+
+        if item.prefix.id != 0 {
+            self.tag.set("prefix", Tag::Byte(item.prefix.id));
+        } else {
+            self.tag.remove("prefix");
+        }
+
+        if item.favourited {
+            self.tag.set("fav", Tag::Byte(1));
+        } else {
+            self.tag.remove("fav");
+        }
+    }
+}
+
+/// The tModLoader `.tplr` file saved beside a modded player's `.plr`.
+///
+/// Items are moved into the player's slots (see `Item::mod_item`) while loaded, everything
+/// else is kept as-is.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ModPlayer {
+    pub root: TagCompound,
+    /// Items whose slot doesn't exist, which are saved back unchanged.
+    unplaced: Vec<(String, TagCompound)>,
+}
+
+fn item_slot<'a>(player: &'a mut Player, key: &str, slot: usize) -> Option<&'a mut Item> {
+    let loadout_index = player.current_loadout_index.max(0) as usize;
+
+    match key {
+        "armor" => {
+            let loadout = player.loadouts.get_mut(loadout_index)?;
+            let mut slot = slot;
+            for items in [
+                &mut loadout.armor[..],
+                &mut loadout.accessories[..],
+                &mut loadout.vanity_armor[..],
+                &mut loadout.vanity_accessories[..],
+            ] {
+                if slot < items.len() {
+                    return items.get_mut(slot);
+                }
+                slot -= items.len();
+            }
+            None
+        }
+        "dye" => {
+            let loadout = player.loadouts.get_mut(loadout_index)?;
+            if slot < ARMOR_COUNT {
+                loadout.armor_dyes.get_mut(slot)
+            } else {
+                loadout.accessory_dyes.get_mut(slot - ARMOR_COUNT)
+            }
+        }
+        "inventory" => {
+            let mut slot = slot;
+            for items in [
+                &mut player.inventory[..],
+                &mut player.coins[..],
+                &mut player.ammo[..],
+            ] {
+                if slot < items.len() {
+                    return items.get_mut(slot);
+                }
+                slot -= items.len();
+            }
+            None
+        }
+        "miscEquips" => player.equipment.get_mut(slot),
+        "miscDyes" => player.equipment_dyes.get_mut(slot),
+        "bank" => player.piggy_bank.get_mut(slot),
+        "bank2" => player.safe.get_mut(slot),
+        "bank3" => player.defenders_forge.get_mut(slot),
+        "bank4" => player.void_vault.get_mut(slot),
+        _ => None,
+    }
+}
+
+/// How many slots each item list covers.
+fn slot_count(key: &str) -> usize {
+    match key {
+        "armor" => (ARMOR_COUNT + ACCESSORY_COUNT) * 2,
+        "dye" => ARMOR_COUNT + ACCESSORY_COUNT,
+        "inventory" => INVENTORY_COUNT + COINS_COUNT + AMMO_COUNT,
+        "miscEquips" | "miscDyes" => EQUIPMENT_COUNT,
+        _ => BANK_COUNT,
+    }
+}
+
+impl ModPlayer {
+    pub fn load(filepath: &Path) -> anyhow::Result<Self> {
+        let file = File::open(filepath)?;
+        let mut reader = GzDecoder::new(BufReader::new(file));
+
+        Ok(Self {
+            root: TagCompound::load_root(&mut reader)?,
+            unplaced: Vec::new(),
+        })
+    }
+
+    pub fn save(&self, filepath: &Path) -> anyhow::Result<()> {
+        let file = File::create(filepath)?;
+        let mut writer = GzEncoder::new(BufWriter::new(file), Compression::default());
+        self.root.save_root(&mut writer)?;
+        writer.finish()?.flush()?;
+
+        Ok(())
+    }
+
+    /// Moves every item out of the item lists and into the player's slots.
+    pub fn take_items(&mut self, player: &mut Player) {
+        self.unplaced.clear();
+
+        for key in ITEM_LIST_KEYS {
+            let Some(Tag::List(_, list)) = self.root.get_mut(key) else {
+                continue;
+            };
+
+            for tag in std::mem::take(list) {
+                let Tag::Compound(mut tag) = tag else {
+                    continue;
+                };
+
+                let slot = tag.get_i64("slot").unwrap_or(-1);
+                let item = usize::try_from(slot)
+                    .ok()
+                    .and_then(|slot| item_slot(player, key, slot));
+
+                match item {
+                    Some(item) => {
+                        tag.remove("slot");
+                        item.mod_item = Some(Box::new(ModItem { tag }));
+                    }
+                    None => self.unplaced.push((key.to_owned(), tag)),
+                }
+            }
+        }
+    }
+
+    /// Builds the `.tplr` contents from the items currently in the player's slots.
+    ///
+    /// Modded items are dropped if their slot now holds a vanilla item, as are vanilla
+    /// items whose id has changed (tModLoader would otherwise overwrite the edit).
+    pub fn with_items(&self, player: &Player) -> Self {
+        let mut root = self.root.clone();
+        let mut player = player.clone();
+
+        for key in ITEM_LIST_KEYS {
+            let mut list = Vec::new();
+
+            for slot in 0..slot_count(key) {
+                let Some(item) = item_slot(&mut player, key, slot) else {
+                    continue;
+                };
+                let Some(mod_item) = item.mod_item.as_deref() else {
+                    continue;
+                };
+
+                let mut mod_item = mod_item.clone();
+                if mod_item.is_vanilla() {
+                    if mod_item.vanilla_id() != Some(item.id) {
+                        continue;
+                    }
+                    mod_item.sync_vanilla(item);
+                } else if item.id != 0 {
+                    continue;
+                }
+
+                let mut tag = mod_item.tag;
+                tag.set("slot", Tag::Short(slot as i16));
+                list.push(Tag::Compound(tag));
+            }
+
+            for (_, tag) in self.unplaced.iter().filter(|(k, _)| k == key) {
+                list.push(Tag::Compound(tag.clone()));
+            }
+
+            if !list.is_empty() || root.get(key).is_some() {
+                root.set(key, Tag::List(Tag::COMPOUND, list));
+            }
+        }
+
+        Self {
+            root,
+            unplaced: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use std::io::{Read, Write};
+
+use byteorder::{ReadBytesExt, WriteBytesExt, BE};
+
+#[derive(thiserror::Error, Debug)]
+pub enum TagError {
+    #[error("Unknown tag type {0}.")]
+    UnknownType(u8),
+    #[error("Expected the root tag to be a compound.")]
+    RootNotCompound,
+    #[error("List elements must all be the same type.")]
+    MixedList,
+}
+
+/// A tModLoader tag, which is a slight variation of Minecraft's NBT format.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag {
+    Byte(u8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<u8>),
+    String(String),
+    /// The element type is kept so empty lists are saved unchanged.
+    List(u8, Vec<Tag>),
+    Compound(TagCompound),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+/// A map of named tags, which keeps the order they were loaded in.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct TagCompound(pub Vec<(String, Tag)>);
+
+fn read_string(reader: &mut dyn Read) -> anyhow::Result<String> {
+    let len = reader.read_u16::<BE>()? as usize;
+    let mut buf = vec![0; len];
+    reader.read_exact(&mut buf)?;
+    Ok(String::from_utf8(buf)?)
+}
+
+fn write_string(writer: &mut dyn Write, value: &str) -> anyhow::Result<()> {
+    writer.write_u16::<BE>(value.len() as u16)?;
+    writer.write_all(value.as_bytes())?;
+    Ok(())
+}
+
+fn read_len(reader: &mut dyn Read) -> anyhow::Result<usize> {
+    Ok(reader.read_i32::<BE>()?.max(0) as usize)
+}
+
+impl Tag {
+    pub const END: u8 = 0;
+    pub const BYTE: u8 = 1;
+    pub const SHORT: u8 = 2;
+    pub const INT: u8 = 3;
+    pub const LONG: u8 = 4;
+    pub const FLOAT: u8 = 5;
+    pub const DOUBLE: u8 = 6;
+    pub const BYTE_ARRAY: u8 = 7;
+    pub const STRING: u8 = 8;
+    pub const LIST: u8 = 9;
+    pub const COMPOUND: u8 = 10;
+    pub const INT_ARRAY: u8 = 11;
+    pub const LONG_ARRAY: u8 = 12;
+
+    pub fn id(&self) -> u8 {
+        match self {
+            Tag::Byte(_) => Self::BYTE,
+            Tag::Short(_) => Self::SHORT,
+            Tag::Int(_) => Self::INT,
+            Tag::Long(_) => Self::LONG,
+            Tag::Float(_) => Self::FLOAT,
+            Tag::Double(_) => Self::DOUBLE,
+            Tag::ByteArray(_) => Self::BYTE_ARRAY,
+            Tag::String(_) => Self::STRING,
+            Tag::List(..) => Self::LIST,
+            Tag::Compound(_) => Self::COMPOUND,
+            Tag::IntArray(_) => Self::INT_ARRAY,
+            Tag::LongArray(_) => Self::LONG_ARRAY,
+        }
+    }
+
+    pub fn load(reader: &mut dyn Read, id: u8) -> anyhow::Result<Self> {
+        let tag = match id {
+            Self::BYTE => Tag::Byte(reader.read_u8()?),
+            Self::SHORT => Tag::Short(reader.read_i16::<BE>()?),
+            Self::INT => Tag::Int(reader.read_i32::<BE>()?),
+            Self::LONG => Tag::Long(reader.read_i64::<BE>()?),
+            Self::FLOAT => Tag::Float(reader.read_f32::<BE>()?),
+            Self::DOUBLE => Tag::Double(reader.read_f64::<BE>()?),
+            Self::BYTE_ARRAY => {
+                let mut buf = vec![0; read_len(reader)?];
+                reader.read_exact(&mut buf)?;
+                Tag::ByteArray(buf)
+            }
+            Self::STRING => Tag::String(read_string(reader)?),
+            Self::LIST => {
+                let element_id = reader.read_u8()?;
+                let len = read_len(reader)?;
+                let mut list = Vec::with_capacity(len);
+                for _ in 0..len {
+                    list.push(Tag::load(reader, element_id)?);
+                }
+                Tag::List(element_id, list)
+            }
+            Self::COMPOUND => Tag::Compound(TagCompound::load(reader)?),
+            Self::INT_ARRAY => {
+                let len = read_len(reader)?;
+                let mut array = Vec::with_capacity(len);
+                for _ in 0..len {
+                    array.push(reader.read_i32::<BE>()?);
+                }
+                Tag::IntArray(array)
+            }
+            Self::LONG_ARRAY => {
+                let len = read_len(reader)?;
+                let mut array = Vec::with_capacity(len);
+                for _ in 0..len {
+                    array.push(reader.read_i64::<BE>()?);
+                }
+                Tag::LongArray(array)
+            }
+            _ => return Err(TagError::UnknownType(id).into()),
+        };
+
+        Ok(tag)
+    }
+
+    pub fn save(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        match self {
+            Tag::Byte(v) => writer.write_u8(*v)?,
+            Tag::Short(v) => writer.write_i16::<BE>(*v)?,
+            Tag::Int(v) => writer.write_i32::<BE>(*v)?,
+            Tag::Long(v) => writer.write_i64::<BE>(*v)?,
+            Tag::Float(v) => writer.write_f32::<BE>(*v)?,
+            Tag::Double(v) => writer.write_f64::<BE>(*v)?,
+            Tag::ByteArray(v) => {
+                writer.write_i32::<BE>(v.len() as i32)?;
+                writer.write_all(v)?;
+            }
+            Tag::String(v) => write_string(writer, v)?,
+            Tag::List(element_id, list) => {
+                if list.iter().any(|t| t.id() != *element_id) {
+                    return Err(TagError::MixedList.into());
+                }
+
+                writer.write_u8(*element_id)?;
+                writer.write_i32::<BE>(list.len() as i32)?;
+                for tag in list {
+                    tag.save(writer)?;
+                }
+            }
+            Tag::Compound(v) => v.save(writer)?,
+            Tag::IntArray(v) => {
+                writer.write_i32::<BE>(v.len() as i32)?;
+                for i in v {
+                    writer.write_i32::<BE>(*i)?;
+                }
+            }
+            Tag::LongArray(v) => {
+                writer.write_i32::<BE>(v.len() as i32)?;
+                for i in v {
+                    writer.write_i64::<BE>(*i)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn as_compound(&self) -> Option<&TagCompound> {
+        match self {
+            Tag::Compound(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Tag::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Any integer tag, widened to an i64.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Tag::Byte(v) => Some(*v as i64),
+            Tag::Short(v) => Some(*v as i64),
+            Tag::Int(v) => Some(*v as i64),
+            Tag::Long(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+impl TagCompound {
+    /// Loads the tags of a compound, up to and including its end tag.
+    pub fn load(reader: &mut dyn Read) -> anyhow::Result<Self> {
+        let mut compound = TagCompound::default();
+
+        loop {
+            let id = reader.read_u8()?;
+            if id == Tag::END {
+                break;
+            }
+
+            let name = read_string(reader)?;
+            let tag = Tag::load(reader, id)?;
+            compound.0.push((name, tag));
+        }
+
+        Ok(compound)
+    }
+
+    pub fn save(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        for (name, tag) in self.0.iter() {
+            writer.write_u8(tag.id())?;
+            write_string(writer, name)?;
+            tag.save(writer)?;
+        }
+        writer.write_u8(Tag::END)?;
+
+        Ok(())
+    }
+
+    /// Loads a named root compound, as written by tModLoader's `TagIO.Write`.
+    pub fn load_root(reader: &mut dyn Read) -> anyhow::Result<Self> {
+        if reader.read_u8()? != Tag::COMPOUND {
+            return Err(TagError::RootNotCompound.into());
+        }
+        let _ = read_string(reader)?;
+
+        TagCompound::load(reader)
+    }
+
+    pub fn save_root(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        writer.write_u8(Tag::COMPOUND)?;
+        write_string(writer, "")?;
+
+        self.save(writer)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Tag> {
+        self.0.iter().find(|(n, _)| n == name).map(|(_, t)| t)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Tag> {
+        self.0.iter_mut().find(|(n, _)| n == name).map(|(_, t)| t)
+    }
+
+    /// Replaces the tag with the same name, keeping its position, or adds it to the end.
+    pub fn set(&mut self, name: &str, tag: Tag) {
+        match self.get_mut(name) {
+            Some(existing) => *existing = tag,
+            None => self.0.push((name.to_owned(), tag)),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Tag> {
+        let index = self.0.iter().position(|(n, _)| n == name)?;
+        Some(self.0.remove(index).1)
+    }
+
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        self.get(name).and_then(Tag::as_str)
+    }
+
+    pub fn get_i64(&self, name: &str) -> Option<i64> {
+        self.get(name).and_then(Tag::as_i64)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Tag)> {
+        self.0.iter().map(|(n, t)| (n.as_str(), t))
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt::Display,
+};
+
+use crate::{ContainerKind, Item, MetaBundle, Player, SlotRef, COIN_IDS, MAX_LIFE, MAX_MANA};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum Severity {
+    /// The game will load the player, but will change or ignore the value.
+    Warning,
+    /// The game is likely to fail to load the player, or crash while playing it.
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warning => write!(f, "Warning"),
+            Self::Error => write!(f, "Error"),
+        }
+    }
+}
+
+/// A problem found by `Player::validate`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum DiagnosticKind {
+    UnknownItem {
+        id: i32,
+    },
+    UnknownPrefix {
+        id: u8,
+    },
+    UnknownBuff {
+        id: i32,
+    },
+    StackTooLarge {
+        id: i32,
+        stack: i32,
+        max_stack: i32,
+    },
+    /// The item can't be obtained normally, e.g. unused or developer items.
+    ForbiddenItem {
+        id: i32,
+    },
+    LifeAboveCap {
+        max_life: i32,
+    },
+    ManaAboveCap {
+        max_mana: i32,
+    },
+    /// The same accessory is equipped more than once in a loadout, `other` is the first slot.
+    DuplicateAccessory {
+        id: i32,
+        other: String,
+    },
+    /// The item isn't one the game allows in an ammo slot.
+    NotAmmo {
+        id: i32,
+    },
+    NotACoin {
+        id: i32,
+    },
+    NegativeBuffTime {
+        id: i32,
+        time: i32,
+    },
+}
+
+impl DiagnosticKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::UnknownItem { .. } | Self::UnknownPrefix { .. } | Self::UnknownBuff { .. } => {
+                Severity::Error
+            }
+            _ => Severity::Warning,
+        }
+    }
+}
+
+impl Display for DiagnosticKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownItem { id } => write!(f, "Item {id} doesn't exist"),
+            Self::UnknownPrefix { id } => write!(f, "Prefix {id} doesn't exist"),
+            Self::UnknownBuff { id } => write!(f, "Buff {id} doesn't exist"),
+            Self::StackTooLarge {
+                id,
+                stack,
+                max_stack,
+            } => write!(
+                f,
+                "Item {id} is stacked to {stack}, but only stacks up to {max_stack}"
+            ),
+            Self::ForbiddenItem { id } => write!(f, "Item {id} can't be obtained in-game"),
+            Self::LifeAboveCap { max_life } => {
+                write!(f, "Max life is {max_life}, above the cap of {MAX_LIFE}")
+            }
+            Self::ManaAboveCap { max_mana } => {
+                write!(f, "Max mana is {max_mana}, above the cap of {MAX_MANA}")
+            }
+            Self::DuplicateAccessory { id, other } => {
+                write!(f, "Accessory {id} is also equipped in {other}")
+            }
+            Self::NotAmmo { id } => write!(f, "Item {id} doesn't belong in an ammo slot"),
+            Self::NotACoin { id } => write!(f, "Item {id} isn't a coin"),
+            Self::NegativeBuffTime { id, time } => {
+                write!(f, "Buff {id} has a negative duration ({time})")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// The field or slot the problem is in, e.g. `loadouts[1].accessories[3]`.
+    pub path: String,
+    pub kind: DiagnosticKind,
+}
+
+impl Diagnostic {
+    pub fn new(path: impl Into<String>, kind: DiagnosticKind) -> Self {
+        Self {
+            severity: kind.severity(),
+            path: path.into(),
+            kind,
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} in {}: {}", self.severity, self.path, self.kind)
+    }
+}
+
+struct Validator<'a> {
+    meta: &'a MetaBundle,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Validator<'_> {
+    fn push(&mut self, path: impl Into<String>, kind: DiagnosticKind) {
+        self.diagnostics.push(Diagnostic::new(path, kind));
+    }
+
+    fn item(&mut self, path: impl FnOnce() -> String, item: &Item) {
+        if item.id == 0 {
+            return;
+        }
+
+        let Some(meta) = self.meta.items.get(item.id) else {
+            self.push(path(), DiagnosticKind::UnknownItem { id: item.id });
+            return;
+        };
+
+        let mut diagnostics = Vec::new();
+
+        if item.prefix.id != 0 && self.meta.prefixes.get(item.prefix.id).is_none() {
+            diagnostics.push(DiagnosticKind::UnknownPrefix { id: item.prefix.id });
+        }
+        let max_stack = meta.effective_max_stack();
+        if item.stack > max_stack {
+            diagnostics.push(DiagnosticKind::StackTooLarge {
+                id: item.id,
+                stack: item.stack,
+                max_stack,
+            });
+        }
+        if meta.forbidden.is_some_and(|f| f) {
+            diagnostics.push(DiagnosticKind::ForbiddenItem { id: item.id });
+        }
+
+        if !diagnostics.is_empty() {
+            let path = path();
+            for kind in diagnostics {
+                self.push(path.clone(), kind);
+            }
+        }
+    }
+}
+
+impl Player {
+    /// Checks the player for values the game wouldn't produce itself, which are often the
+    /// reason a save fails to load in-game.
+    ///
+    /// Diagnostics are in slot order. Nothing is changed, see `sanitize` for that.
+    pub fn validate(&self, meta: &MetaBundle) -> Vec<Diagnostic> {
+        let mut validator = Validator {
+            meta,
+            diagnostics: Vec::new(),
+        };
+
+        if self.max_life > MAX_LIFE {
+            validator.push(
+                "max_life",
+                DiagnosticKind::LifeAboveCap {
+                    max_life: self.max_life,
+                },
+            );
+        }
+        if self.max_mana > MAX_MANA {
+            validator.push(
+                "max_mana",
+                DiagnosticKind::ManaAboveCap {
+                    max_mana: self.max_mana,
+                },
+            );
+        }
+
+        // The game won't let the same accessory be equipped twice in a loadout, even as vanity
+        let mut equipped: HashMap<(usize, i32), SlotRef> = HashMap::new();
+
+        for (slot, item) in self.iter_slots() {
+            validator.item(|| slot.to_string(), item);
+            if item.id == 0 {
+                continue;
+            }
+
+            match slot.container {
+                ContainerKind::Coins if !COIN_IDS.contains(&item.id) => {
+                    validator.push(slot.to_string(), DiagnosticKind::NotACoin { id: item.id });
+                }
+                ContainerKind::Ammo
+                    if !meta.items.get(item.id).is_some_and(|m| m.fits_ammo_slot()) =>
+                {
+                    validator.push(slot.to_string(), DiagnosticKind::NotAmmo { id: item.id });
+                }
+                ContainerKind::Accessories(loadout) | ContainerKind::VanityAccessories(loadout) => {
+                    match equipped.entry((loadout, item.id)) {
+                        Entry::Occupied(other) => validator.push(
+                            slot.to_string(),
+                            DiagnosticKind::DuplicateAccessory {
+                                id: item.id,
+                                other: other.get().to_string(),
+                            },
+                        ),
+                        Entry::Vacant(entry) => {
+                            entry.insert(slot);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (i, buff) in self.buffs.iter().enumerate() {
+            if buff.id == 0 {
+                continue;
+            }
+
+            if meta.buffs.get(buff.id).is_none() {
+                validator.push(
+                    format!("buffs[{i}]"),
+                    DiagnosticKind::UnknownBuff { id: buff.id },
+                );
+            }
+            if buff.time < 0 {
+                validator.push(
+                    format!("buffs[{i}]"),
+                    DiagnosticKind::NegativeBuffTime {
+                        id: buff.id,
+                        time: buff.time,
+                    },
+                );
+            }
+        }
+
+        validator.diagnostics
+    }
+}
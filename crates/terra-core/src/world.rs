@@ -0,0 +1,108 @@
+mod chest;
+mod tile;
+mod tile_grid;
+mod world_data;
+
+use std::fmt::Display;
+
+pub use chest::Chest;
+pub use tile::{BrickStyle, LiquidType, Tile};
+pub use tile_grid::TileGrid;
+pub use world_data::{World, WorldError};
+
+#[repr(i32)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serialize", derive(serde_repr::Serialize_repr))]
+#[cfg_attr(feature = "deserialize", derive(serde_repr::Deserialize_repr))]
+pub enum WorldDifficulty {
+    #[default]
+    Classic = 0,
+    Expert = 1,
+    Master = 2,
+    Journey = 3,
+    Unknown = i32::MAX,
+}
+
+impl From<i32> for WorldDifficulty {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => WorldDifficulty::Classic,
+            1 => WorldDifficulty::Expert,
+            2 => WorldDifficulty::Master,
+            3 => WorldDifficulty::Journey,
+            _ => WorldDifficulty::Unknown,
+        }
+    }
+}
+
+impl From<WorldDifficulty> for i32 {
+    fn from(value: WorldDifficulty) -> Self {
+        match value {
+            WorldDifficulty::Classic => 0,
+            WorldDifficulty::Expert => 1,
+            WorldDifficulty::Master => 2,
+            WorldDifficulty::Journey => 3,
+            WorldDifficulty::Unknown => i32::MAX,
+        }
+    }
+}
+
+impl Display for WorldDifficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                WorldDifficulty::Classic => "Classic",
+                WorldDifficulty::Expert => "Expert",
+                WorldDifficulty::Master => "Master",
+                WorldDifficulty::Journey => "Journey",
+                WorldDifficulty::Unknown => "Unknown",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum WorldSize {
+    Small,
+    Medium,
+    Large,
+    Custom,
+}
+
+impl WorldSize {
+    /// Width and height (in tiles) of the sizes available when creating a world.
+    pub fn dimensions(&self) -> Option<(i32, i32)> {
+        match self {
+            WorldSize::Small => Some((4200, 1200)),
+            WorldSize::Medium => Some((6400, 1800)),
+            WorldSize::Large => Some((8400, 2400)),
+            WorldSize::Custom => None,
+        }
+    }
+
+    pub fn from_dimensions(width: i32, height: i32) -> Self {
+        [WorldSize::Small, WorldSize::Medium, WorldSize::Large]
+            .into_iter()
+            .find(|s| s.dimensions() == Some((width, height)))
+            .unwrap_or(WorldSize::Custom)
+    }
+}
+
+impl Display for WorldSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                WorldSize::Small => "Small",
+                WorldSize::Medium => "Medium",
+                WorldSize::Large => "Large",
+                WorldSize::Custom => "Custom",
+            }
+        )
+    }
+}
@@ -0,0 +1,97 @@
+use std::io::{Read, Write};
+
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
+use serde_big_array::BigArray;
+
+use crate::{
+    ext::{TerraReadExt, TerraWriteExt},
+    Item, CHEST_ITEM_COUNT,
+};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct Chest {
+    /// Tile position of the chest's top-left corner.
+    pub x: i32,
+    pub y: i32,
+    pub name: String,
+    #[serde(with = "BigArray")]
+    pub items: [Item; CHEST_ITEM_COUNT],
+}
+
+impl Default for Chest {
+    fn default() -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            name: String::new(),
+            items: std::array::from_fn(|_| Item::default()),
+        }
+    }
+}
+
+impl Chest {
+    /// Chest items are stored as `stack, [id, prefix]`, rather than the `id, stack, prefix`
+    /// used by players, so `Item::load` can't be used here.
+    fn load_item(item: &mut Item, reader: &mut dyn Read) -> anyhow::Result<()> {
+        *item = Item::default();
+
+        let stack = reader.read_i16::<LE>()?;
+        if stack > 0 {
+            item.id = reader.read_i32::<LE>()?;
+            item.stack = stack as i32;
+            item.prefix.load(reader)?;
+        }
+
+        Ok(())
+    }
+
+    fn save_item(item: &Item, writer: &mut dyn Write) -> anyhow::Result<()> {
+        if item.id == 0 || item.stack <= 0 {
+            writer.write_i16::<LE>(0)?;
+        } else {
+            writer.write_i16::<LE>(item.stack.min(i16::MAX as i32) as i16)?;
+            writer.write_i32::<LE>(item.id)?;
+            item.prefix.save(writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// `item_count` is the number of items stored per chest in the file, any beyond
+    /// `CHEST_ITEM_COUNT` are discarded (as the game does).
+    pub fn load(&mut self, reader: &mut dyn Read, item_count: usize) -> anyhow::Result<()> {
+        self.x = reader.read_i32::<LE>()?;
+        self.y = reader.read_i32::<LE>()?;
+        self.name = reader.read_lpstring()?;
+
+        let mut discarded = Item::default();
+        for i in 0..item_count {
+            let item = self.items.get_mut(i).unwrap_or(&mut discarded);
+            Self::load_item(item, reader)?;
+        }
+
+        for item in self.items.iter_mut().skip(item_count) {
+            *item = Item::default();
+        }
+
+        Ok(())
+    }
+
+    pub fn save(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        writer.write_i32::<LE>(self.x)?;
+        writer.write_i32::<LE>(self.y)?;
+        writer.write_lpstring(&self.name)?;
+
+        for item in self.items.iter() {
+            Self::save_item(item, writer)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.iter().all(|i| i.id == 0)
+    }
+}
@@ -0,0 +1,184 @@
+use std::io::Read;
+
+use byteorder::{ReadBytesExt, LE};
+
+use crate::WorldError;
+
+#[repr(u8)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde_repr::Serialize_repr))]
+#[cfg_attr(feature = "deserialize", derive(serde_repr::Deserialize_repr))]
+pub enum LiquidType {
+    #[default]
+    None = 0,
+    Water = 1,
+    Lava = 2,
+    Honey = 3,
+    Shimmer = 4,
+}
+
+#[repr(u8)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde_repr::Serialize_repr))]
+#[cfg_attr(feature = "deserialize", derive(serde_repr::Deserialize_repr))]
+pub enum BrickStyle {
+    #[default]
+    Full = 0,
+    HalfBrick = 1,
+    SlopeDownRight = 2,
+    SlopeDownLeft = 3,
+    SlopeUpRight = 4,
+    SlopeUpLeft = 5,
+}
+
+impl From<u8> for BrickStyle {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => BrickStyle::HalfBrick,
+            2 => BrickStyle::SlopeDownRight,
+            3 => BrickStyle::SlopeDownLeft,
+            4 => BrickStyle::SlopeUpRight,
+            5 => BrickStyle::SlopeUpLeft,
+            _ => BrickStyle::Full,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct Tile {
+    pub active: bool,
+    pub tile_type: u16,
+    /// Only stored for frame-important tiles, otherwise -1.
+    pub frame_x: i16,
+    pub frame_y: i16,
+    pub tile_color: u8,
+    pub brick_style: BrickStyle,
+
+    pub wall: u16,
+    pub wall_color: u8,
+
+    pub liquid: LiquidType,
+    pub liquid_amount: u8,
+
+    pub red_wire: bool,
+    pub blue_wire: bool,
+    pub green_wire: bool,
+    pub yellow_wire: bool,
+    pub actuator: bool,
+    pub actuated: bool,
+
+    pub invisible_block: bool,
+    pub invisible_wall: bool,
+    pub fullbright_block: bool,
+    pub fullbright_wall: bool,
+}
+
+impl Tile {
+    /// Loads a single tile, returning how many times it is repeated below itself.
+    pub fn load(&mut self, reader: &mut dyn Read, important: &[bool]) -> anyhow::Result<usize> {
+        *self = Tile::default();
+
+        // Each header byte's lowest bit marks whether the next header byte is present
+        let header1 = reader.read_u8()?;
+        let mut header2 = 0;
+        let mut header3 = 0;
+        let mut header4 = 0;
+
+        if header1 & 0b1 != 0 {
+            header2 = reader.read_u8()?;
+            if header2 & 0b1 != 0 {
+                header3 = reader.read_u8()?;
+                if header3 & 0b1 != 0 {
+                    header4 = reader.read_u8()?;
+                }
+            }
+        }
+
+        if header1 & 0b10 != 0 {
+            self.active = true;
+
+            self.tile_type = if header1 & 0b10_0000 != 0 {
+                reader.read_u16::<LE>()?
+            } else {
+                reader.read_u8()? as u16
+            };
+
+            let Some(&is_important) = important.get(self.tile_type as usize) else {
+                return Err(WorldError::Corrupted.into());
+            };
+
+            if is_important {
+                self.frame_x = reader.read_i16::<LE>()?;
+                self.frame_y = reader.read_i16::<LE>()?;
+
+                // 144 - Timer
+                if self.tile_type == 144 {
+                    self.frame_y = 0;
+                }
+            } else {
+                self.frame_x = -1;
+                self.frame_y = -1;
+            }
+
+            if header3 & 0b1000 != 0 {
+                self.tile_color = reader.read_u8()?;
+            }
+        }
+
+        if header1 & 0b100 != 0 {
+            self.wall = reader.read_u8()? as u16;
+
+            if header3 & 0b1_0000 != 0 {
+                self.wall_color = reader.read_u8()?;
+            }
+        }
+
+        let liquid = (header1 & 0b1_1000) >> 3;
+        if liquid != 0 {
+            self.liquid_amount = reader.read_u8()?;
+            self.liquid = if header3 & 0b1000_0000 != 0 {
+                LiquidType::Shimmer
+            } else {
+                match liquid {
+                    1 => LiquidType::Water,
+                    2 => LiquidType::Lava,
+                    _ => LiquidType::Honey,
+                }
+            };
+        }
+
+        if header2 > 1 {
+            self.red_wire = header2 & 0b10 != 0;
+            self.blue_wire = header2 & 0b100 != 0;
+            self.green_wire = header2 & 0b1000 != 0;
+            self.brick_style = BrickStyle::from((header2 & 0b111_0000) >> 4);
+        }
+
+        if header3 > 1 {
+            self.actuator = header3 & 0b10 != 0;
+            self.actuated = header3 & 0b100 != 0;
+            self.yellow_wire = header3 & 0b10_0000 != 0;
+
+            if header3 & 0b100_0000 != 0 {
+                self.wall |= (reader.read_u8()? as u16) << 8;
+            }
+        }
+
+        if header4 > 1 {
+            self.invisible_block = header4 & 0b10 != 0;
+            self.invisible_wall = header4 & 0b100 != 0;
+            self.fullbright_block = header4 & 0b1000 != 0;
+            self.fullbright_wall = header4 & 0b1_0000 != 0;
+        }
+
+        let repeat = match (header1 & 0b1100_0000) >> 6 {
+            0 => 0,
+            1 => reader.read_u8()? as i16,
+            _ => reader.read_i16::<LE>()?,
+        };
+
+        Ok(usize::try_from(repeat).map_err(|_| WorldError::Corrupted)?)
+    }
+}
@@ -0,0 +1,130 @@
+use std::{
+    io::Read,
+    ops::{Index, IndexMut},
+};
+
+use crate::{Tile, WorldError};
+
+/// Tiles stored column by column, matching the order they appear in the world file.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct TileGrid {
+    width: usize,
+    height: usize,
+    tiles: Vec<Tile>,
+}
+
+impl TileGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            tiles: vec![Tile::default(); width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn load(&mut self, reader: &mut dyn Read, important: &[bool]) -> anyhow::Result<()> {
+        let mut tile = Tile::default();
+
+        for x in 0..self.width {
+            let column = &mut self.tiles[x * self.height..(x + 1) * self.height];
+            let mut y = 0;
+
+            while y < column.len() {
+                let repeat = tile.load(reader, important)?;
+                let end = y
+                    .checked_add(repeat)
+                    .filter(|&end| end < column.len())
+                    .ok_or(WorldError::Corrupted)?;
+
+                column[y..=end].fill(tile.clone());
+                y = end + 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn offset(&self, x: usize, y: usize) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(x * self.height + y)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&Tile> {
+        self.offset(x, y).map(|i| &self.tiles[i])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut Tile> {
+        self.offset(x, y).map(|i| &mut self.tiles[i])
+    }
+
+    /// All tiles in column `x`, from top to bottom.
+    pub fn column(&self, x: usize) -> Option<&[Tile]> {
+        (x < self.width).then(|| &self.tiles[x * self.height..(x + 1) * self.height])
+    }
+
+    /// Iterates over every tile as `(x, y, tile)`, column by column.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &Tile)> {
+        let height = self.height;
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(move |(i, tile)| (i / height, i % height, tile))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (usize, usize, &mut Tile)> {
+        let height = self.height;
+        self.tiles
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, tile)| (i / height, i % height, tile))
+    }
+
+    /// Iterates over the tiles within the given rectangle, clamped to the grid.
+    pub fn iter_region(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> impl Iterator<Item = (usize, usize, &Tile)> {
+        let right = (x + width).min(self.width);
+        let bottom = (y + height).min(self.height);
+
+        (x..right).flat_map(move |tx| {
+            (y..bottom).map(move |ty| (tx, ty, &self.tiles[tx * self.height + ty]))
+        })
+    }
+
+    /// Counts the active tiles of the given type.
+    pub fn count(&self, tile_type: u16) -> usize {
+        self.tiles
+            .iter()
+            .filter(|t| t.active && t.tile_type == tile_type)
+            .count()
+    }
+}
+
+impl Index<(usize, usize)> for TileGrid {
+    type Output = Tile;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        self.get(x, y).expect("tile position out of bounds")
+    }
+}
+
+impl IndexMut<(usize, usize)> for TileGrid {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+        self.get_mut(x, y).expect("tile position out of bounds")
+    }
+}
@@ -0,0 +1,553 @@
+use std::{
+    fs::File,
+    io::{Cursor, ErrorKind, Read, Write},
+    path::Path,
+};
+
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
+
+use crate::{
+    ext::TerraReadExt, Chest, FileType, TileGrid, WorldDifficulty, WorldSize, CHEST_ITEM_COUNT,
+    CURRENT_VERSION, MAGIC_MASK, MAGIC_NUMBER,
+};
+
+/// Worlds older than this use the pre-1.2 flat format, which has no section table.
+const SECTIONED_FORMAT_VERSION: i32 = 88;
+
+const HEADER_SECTION: usize = 0;
+const TILES_SECTION: usize = 1;
+const CHESTS_SECTION: usize = 2;
+
+#[derive(thiserror::Error, Debug)]
+pub enum WorldError {
+    #[error("Unknown error with file.")]
+    Failure,
+    #[error("The file cannot be read by the user.")]
+    AccessDenied,
+    #[error("The file was not found.")]
+    FileNotFound,
+    #[error("The file is for a newer version of Terraria ({0}) than terra-rs supports (<= {CURRENT_VERSION}).")]
+    PostDated(i32),
+    #[error("The file is for an older version of Terraria ({0}) than terra-rs supports (>= {SECTIONED_FORMAT_VERSION}).")]
+    PreDated(i32),
+    #[error("The file is corrupted.")]
+    Corrupted,
+    #[error("Expected Re-Logic file format.")]
+    IncorrectFormat,
+    #[error("Found incorrect file type.")]
+    IncorrectFileType,
+}
+
+/// A `.wld` file.
+///
+/// Only the metadata at the start of the header section and the chests are parsed;
+/// every other section is kept as raw bytes so that data terra-rs doesn't understand
+/// survives a save. Changes to the header fields are not saved.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct World {
+    pub version: i32,
+    pub revision: u32,
+    pub favourited: u64,
+
+    /// Whether each tile type stores its frame (u/v) coordinates in the tile data.
+    pub tile_frame_important: Vec<bool>,
+
+    pub name: String,
+    pub seed: String,
+    pub generator_version: u64,
+    pub unique_id: [u8; 16],
+    pub id: i32,
+
+    pub left: i32,
+    pub right: i32,
+    pub top: i32,
+    pub bottom: i32,
+    pub width: i32,
+    pub height: i32,
+
+    pub difficulty: WorldDifficulty,
+
+    pub drunk_world: bool,
+    pub get_good_world: bool,
+    pub tenth_anniversary_world: bool,
+    pub dont_starve_world: bool,
+    pub not_the_bees_world: bool,
+    pub remix_world: bool,
+    pub no_traps_world: bool,
+    pub zenith_world: bool,
+
+    pub creation_time: i64,
+
+    pub spawn_x: i32,
+    pub spawn_y: i32,
+    pub surface_level: f64,
+    pub rock_level: f64,
+    pub dungeon_x: i32,
+    pub dungeon_y: i32,
+
+    pub crimson: bool,
+
+    pub downed_eye_of_cthulhu: bool,
+    pub downed_evil_boss: bool,
+    pub downed_skeletron: bool,
+    pub downed_queen_bee: bool,
+    pub downed_destroyer: bool,
+    pub downed_twins: bool,
+    pub downed_skeletron_prime: bool,
+    pub downed_any_mech_boss: bool,
+    pub downed_plantera: bool,
+    pub downed_golem: bool,
+    pub downed_king_slime: bool,
+
+    pub saved_goblin: bool,
+    pub saved_wizard: bool,
+    pub saved_mechanic: bool,
+    pub downed_goblins: bool,
+    pub downed_clown: bool,
+    pub downed_frost_legion: bool,
+    pub downed_pirates: bool,
+
+    pub shadow_orb_smashed: bool,
+    pub spawn_meteor: bool,
+    pub shadow_orb_count: u8,
+    pub altar_count: i32,
+    pub hard_mode: bool,
+
+    pub chests: Vec<Chest>,
+
+    /// The raw bytes of every section, in file order. The final entry is the footer.
+    #[serde(skip)]
+    pub(crate) sections: Vec<Vec<u8>>,
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            revision: 0,
+            favourited: 0,
+
+            tile_frame_important: Vec::new(),
+
+            name: "World".to_owned(),
+            seed: String::new(),
+            generator_version: 0,
+            unique_id: [0; 16],
+            id: 0,
+
+            left: 0,
+            right: 0,
+            top: 0,
+            bottom: 0,
+            width: 0,
+            height: 0,
+
+            difficulty: WorldDifficulty::Classic,
+
+            drunk_world: false,
+            get_good_world: false,
+            tenth_anniversary_world: false,
+            dont_starve_world: false,
+            not_the_bees_world: false,
+            remix_world: false,
+            no_traps_world: false,
+            zenith_world: false,
+
+            creation_time: 0,
+
+            spawn_x: 0,
+            spawn_y: 0,
+            surface_level: 0.,
+            rock_level: 0.,
+            dungeon_x: 0,
+            dungeon_y: 0,
+
+            crimson: false,
+
+            downed_eye_of_cthulhu: false,
+            downed_evil_boss: false,
+            downed_skeletron: false,
+            downed_queen_bee: false,
+            downed_destroyer: false,
+            downed_twins: false,
+            downed_skeletron_prime: false,
+            downed_any_mech_boss: false,
+            downed_plantera: false,
+            downed_golem: false,
+            downed_king_slime: false,
+
+            saved_goblin: false,
+            saved_wizard: false,
+            saved_mechanic: false,
+            downed_goblins: false,
+            downed_clown: false,
+            downed_frost_legion: false,
+            downed_pirates: false,
+
+            shadow_orb_smashed: false,
+            spawn_meteor: false,
+            shadow_orb_count: 0,
+            altar_count: 0,
+            hard_mode: false,
+
+            chests: Vec::new(),
+
+            sections: Vec::new(),
+        }
+    }
+}
+
+fn map_io_error(e: std::io::Error) -> WorldError {
+    match e.kind() {
+        ErrorKind::NotFound => WorldError::FileNotFound,
+        ErrorKind::PermissionDenied => WorldError::AccessDenied,
+        _ => WorldError::Failure,
+    }
+}
+
+fn open_file(filepath: &Path) -> Result<File, WorldError> {
+    File::open(filepath).map_err(map_io_error)
+}
+
+fn create_file(filepath: &Path) -> Result<File, WorldError> {
+    File::create(filepath).map_err(map_io_error)
+}
+
+impl World {
+    fn load_from_reader(&mut self, reader: &mut dyn Read) -> anyhow::Result<()> {
+        self.version = reader.read_i32::<LE>()?;
+
+        if self.version > CURRENT_VERSION {
+            return Err(WorldError::PostDated(self.version).into());
+        }
+
+        if self.version < SECTIONED_FORMAT_VERSION {
+            return Err(WorldError::PreDated(self.version).into());
+        }
+
+        let mut position = 4;
+
+        if self.version >= 135 {
+            // Same layout as the player header, see `Player::load_from_reader`
+            let magic_num = reader.read_u64::<LE>()?;
+
+            if magic_num & MAGIC_MASK != MAGIC_NUMBER {
+                return Err(WorldError::IncorrectFormat.into());
+            }
+
+            if ((magic_num >> 56) as u8) != FileType::World {
+                return Err(WorldError::IncorrectFileType.into());
+            }
+
+            self.revision = reader.read_u32::<LE>()?;
+            self.favourited = reader.read_u64::<LE>()?;
+
+            position += 8 + 4 + 8;
+        }
+
+        let section_count = reader.read_i16::<LE>()?;
+        if section_count <= 0 {
+            return Err(WorldError::Corrupted.into());
+        }
+
+        let mut pointers = Vec::with_capacity(section_count as usize);
+        for _ in 0..section_count {
+            pointers.push(reader.read_i32::<LE>()?);
+        }
+
+        let important_count = reader.read_i16::<LE>()?.max(0) as usize;
+        self.tile_frame_important = vec![false; important_count];
+
+        // Packed LSB-first, 8 tile types to a byte
+        let mut byte = 0;
+        for (i, important) in self.tile_frame_important.iter_mut().enumerate() {
+            if i.is_multiple_of(8) {
+                byte = reader.read_u8()?;
+            }
+            *important = byte & (1 << (i % 8)) != 0;
+        }
+
+        position += 2 + 4 * pointers.len() + 2 + important_count.div_ceil(8);
+
+        if pointers[0] as usize != position || pointers.windows(2).any(|w| w[0] > w[1]) {
+            return Err(WorldError::Corrupted.into());
+        }
+
+        self.sections.clear();
+        for window in pointers.windows(2) {
+            let mut section = vec![0; (window[1] - window[0]) as usize];
+            reader.read_exact(&mut section)?;
+            self.sections.push(section);
+        }
+
+        let mut footer = Vec::new();
+        reader.read_to_end(&mut footer)?;
+        self.sections.push(footer);
+
+        self.load_header()?;
+        self.load_chests()
+    }
+
+    fn save_to_writer(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        let mut sections = self.sections.clone();
+
+        if let Some(section) = sections.get_mut(CHESTS_SECTION) {
+            section.clear();
+            self.save_chests(section)?;
+        }
+
+        writer.write_i32::<LE>(self.version)?;
+
+        let mut position = 4;
+
+        if self.version >= 135 {
+            writer.write_u64::<LE>(MAGIC_NUMBER | (u64::from(FileType::World) << 56u64))?;
+            writer.write_u32::<LE>(self.revision)?;
+            writer.write_u64::<LE>(self.favourited)?;
+
+            position += 8 + 4 + 8;
+        }
+
+        let important_count = self.tile_frame_important.len();
+        position += 2 + 4 * sections.len() + 2 + important_count.div_ceil(8);
+
+        writer.write_i16::<LE>(sections.len() as i16)?;
+        for section in sections.iter() {
+            writer.write_i32::<LE>(position as i32)?;
+            position += section.len();
+        }
+
+        writer.write_i16::<LE>(important_count as i16)?;
+        for chunk in self.tile_frame_important.chunks(8) {
+            let byte = chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |b, (i, &important)| b | ((important as u8) << i));
+            writer.write_u8(byte)?;
+        }
+
+        for section in sections.iter() {
+            writer.write_all(section)?;
+        }
+
+        Ok(())
+    }
+
+    fn load_header(&mut self) -> anyhow::Result<()> {
+        let mut reader = Cursor::new(self.sections[HEADER_SECTION].clone());
+        let reader: &mut dyn Read = &mut reader;
+
+        self.name = reader.read_lpstring()?;
+
+        if self.version >= 179 {
+            self.seed = if self.version == 179 {
+                reader.read_i32::<LE>()?.to_string()
+            } else {
+                reader.read_lpstring()?
+            };
+            self.generator_version = reader.read_u64::<LE>()?;
+        }
+
+        if self.version >= 181 {
+            reader.read_exact(&mut self.unique_id)?;
+        }
+
+        self.id = reader.read_i32::<LE>()?;
+
+        self.left = reader.read_i32::<LE>()?;
+        self.right = reader.read_i32::<LE>()?;
+        self.top = reader.read_i32::<LE>()?;
+        self.bottom = reader.read_i32::<LE>()?;
+
+        // Yes, height comes first
+        self.height = reader.read_i32::<LE>()?;
+        self.width = reader.read_i32::<LE>()?;
+
+        if self.version >= 209 {
+            self.difficulty = WorldDifficulty::from(reader.read_i32::<LE>()?);
+
+            if self.version >= 222 {
+                self.drunk_world = reader.read_bool()?;
+            }
+            if self.version >= 227 {
+                self.get_good_world = reader.read_bool()?;
+            }
+            if self.version >= 238 {
+                self.tenth_anniversary_world = reader.read_bool()?;
+            }
+            if self.version >= 239 {
+                self.dont_starve_world = reader.read_bool()?;
+            }
+            if self.version >= 241 {
+                self.not_the_bees_world = reader.read_bool()?;
+            }
+            if self.version >= 249 {
+                self.remix_world = reader.read_bool()?;
+            }
+            if self.version >= 266 {
+                self.no_traps_world = reader.read_bool()?;
+            }
+
+            self.zenith_world = if self.version >= 267 {
+                reader.read_bool()?
+            } else {
+                self.remix_world && self.drunk_world
+            };
+        } else {
+            if self.version >= 112 && reader.read_bool()? {
+                self.difficulty = WorldDifficulty::Expert;
+            }
+
+            // Master mode was briefly stored as its own bool
+            if self.version == 208 && reader.read_bool()? {
+                self.difficulty = WorldDifficulty::Master;
+            }
+        }
+
+        if self.version >= 141 {
+            self.creation_time = reader.read_i64::<LE>()?;
+        }
+
+        // Moon type, tree/cave background offsets & styles and the ice/jungle/hell backgrounds
+        let _ = reader.read_u8()?;
+        for _ in 0..(3 + 4 + 3 + 4 + 3) {
+            let _ = reader.read_i32::<LE>()?;
+        }
+
+        self.spawn_x = reader.read_i32::<LE>()?;
+        self.spawn_y = reader.read_i32::<LE>()?;
+        self.surface_level = reader.read_f64::<LE>()?;
+        self.rock_level = reader.read_f64::<LE>()?;
+
+        // Time, day/night, moon phase, blood moon & eclipse
+        let _ = reader.read_f64::<LE>()?;
+        let _ = reader.read_bool()?;
+        let _ = reader.read_i32::<LE>()?;
+        let _ = reader.read_bool()?;
+        let _ = reader.read_bool()?;
+
+        self.dungeon_x = reader.read_i32::<LE>()?;
+        self.dungeon_y = reader.read_i32::<LE>()?;
+
+        self.crimson = reader.read_bool()?;
+
+        self.downed_eye_of_cthulhu = reader.read_bool()?;
+        self.downed_evil_boss = reader.read_bool()?;
+        self.downed_skeletron = reader.read_bool()?;
+        self.downed_queen_bee = reader.read_bool()?;
+        self.downed_destroyer = reader.read_bool()?;
+        self.downed_twins = reader.read_bool()?;
+        self.downed_skeletron_prime = reader.read_bool()?;
+        self.downed_any_mech_boss = reader.read_bool()?;
+        self.downed_plantera = reader.read_bool()?;
+        self.downed_golem = reader.read_bool()?;
+
+        if self.version >= 118 {
+            self.downed_king_slime = reader.read_bool()?;
+        }
+
+        self.saved_goblin = reader.read_bool()?;
+        self.saved_wizard = reader.read_bool()?;
+        self.saved_mechanic = reader.read_bool()?;
+        self.downed_goblins = reader.read_bool()?;
+        self.downed_clown = reader.read_bool()?;
+        self.downed_frost_legion = reader.read_bool()?;
+        self.downed_pirates = reader.read_bool()?;
+
+        self.shadow_orb_smashed = reader.read_bool()?;
+        self.spawn_meteor = reader.read_bool()?;
+        self.shadow_orb_count = reader.read_u8()?;
+        self.altar_count = reader.read_i32::<LE>()?;
+        self.hard_mode = reader.read_bool()?;
+
+        // TODO: The rest of the header (invasions, angler, later bosses, etc.)
+
+        Ok(())
+    }
+
+    fn load_chests(&mut self) -> anyhow::Result<()> {
+        self.chests.clear();
+
+        // The final section is the footer, which is never the chest section
+        if self.sections.len() <= CHESTS_SECTION + 1 {
+            return Ok(());
+        }
+
+        let mut reader = Cursor::new(self.sections[CHESTS_SECTION].as_slice());
+        let reader: &mut dyn Read = &mut reader;
+
+        let chest_count = reader.read_i16::<LE>()?.max(0) as usize;
+        let item_count = reader.read_i16::<LE>()?.max(0) as usize;
+
+        for _ in 0..chest_count {
+            let mut chest = Chest::default();
+            chest.load(reader, item_count)?;
+            self.chests.push(chest);
+        }
+
+        Ok(())
+    }
+
+    fn save_chests(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        writer.write_i16::<LE>(self.chests.len() as i16)?;
+        writer.write_i16::<LE>(CHEST_ITEM_COUNT as i16)?;
+
+        for chest in self.chests.iter() {
+            chest.save(writer)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn load(&mut self, filepath: &Path) -> anyhow::Result<()> {
+        let mut file = open_file(filepath)?;
+        let mut data = Vec::with_capacity(file.metadata().map(|m| m.len() as usize).unwrap_or(0));
+        file.read_to_end(&mut data)?;
+
+        let mut reader = Cursor::new(data);
+        self.load_from_reader(&mut reader)
+    }
+
+    /// Decodes the tile section. This is done on demand as large worlds have tens of millions of tiles.
+    pub fn load_tiles(&self) -> anyhow::Result<TileGrid> {
+        let section = self
+            .sections
+            .get(TILES_SECTION)
+            .ok_or(WorldError::Corrupted)?;
+
+        if self.width <= 0 || self.height <= 0 {
+            return Err(WorldError::Corrupted.into());
+        }
+
+        let mut grid = TileGrid::new(self.width as usize, self.height as usize);
+        let mut reader = Cursor::new(section.as_slice());
+        grid.load(&mut reader, &self.tile_frame_important)?;
+
+        Ok(grid)
+    }
+
+    pub fn save(&self, filepath: &Path) -> anyhow::Result<()> {
+        let mut buf = Vec::new();
+        self.save_to_writer(&mut buf)?;
+
+        let mut file = create_file(filepath)?;
+        file.write_all(&buf)?;
+
+        Ok(())
+    }
+
+    /// The chest at the given tile position (the chest's top-left corner).
+    pub fn chest_at(&self, x: i32, y: i32) -> Option<&Chest> {
+        self.chests.iter().find(|c| c.x == x && c.y == y)
+    }
+
+    pub fn chest_at_mut(&mut self, x: i32, y: i32) -> Option<&mut Chest> {
+        self.chests.iter_mut().find(|c| c.x == x && c.y == y)
+    }
+
+    pub fn size(&self) -> WorldSize {
+        WorldSize::from_dimensions(self.width, self.height)
+    }
+}
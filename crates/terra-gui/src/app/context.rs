@@ -5,12 +5,12 @@ use flume::{Receiver, Sender};
 
 use terra_core::{
     utils::{self, AsTicks},
-    BuffMeta, ItemMeta, Player, PrefixMeta, ResearchItem,
+    FoundItem, Loadout, MetaBundle, Player, Repair, ResearchItem,
 };
 
 use super::{
     inventory::{
-        selected_buff, selected_item, ItemGroup, SelectedBuff, SelectedItem, SelectedLoadout,
+        selected_buff, selected_item_mut, ItemGroup, SelectedBuff, SelectedItem, SelectedLoadout,
     },
     meta::MetaLoader,
     visuals, AppMessage, DEFAULT_PLAYER, DEFAULT_PLAYER_DIR, SHORTCUT_EXIT, SHORTCUT_LOAD,
@@ -31,7 +31,14 @@ pub enum Message {
     ResetPlayer,
     LoadPlayer,
     SavePlayer,
+    RepairPlayer,
+    CloseRepairs,
     SelectLoadout(SelectedLoadout),
+    EquipLoadout(usize),
+    CopyLoadout { from: usize, to: usize },
+    SwapLoadouts(usize, usize),
+    ExportLoadout(usize),
+    ImportLoadout(usize),
     SelectItem(SelectedItem),
     SelectBuff(SelectedBuff),
     AddAllResearch,
@@ -61,19 +68,22 @@ pub struct AppContext {
     pub selected_buff: SelectedBuff,
     pub selected_loadout: SelectedLoadout,
 
-    pub prefix_meta: Arc<RwLock<Vec<PrefixMeta>>>,
-    pub item_meta: Arc<RwLock<Vec<ItemMeta>>>,
-    pub buff_meta: Arc<RwLock<Vec<BuffMeta>>>,
+    pub meta: Arc<RwLock<MetaBundle>>,
 
     pub item_spritesheet: Arc<RwLock<Option<TextureHandle>>>,
     pub buff_spritesheet: Arc<RwLock<Option<TextureHandle>>>,
     pub icon_spritesheet: Arc<RwLock<Option<TextureHandle>>>,
 
     pub search_term: String,
+    /// The "Where is it?" search, by item name or id.
+    pub item_search: String,
+    pub item_search_hits: Vec<FoundItem>,
 
     pub theme: visuals::Theme,
 
     pub error: Option<anyhow::Error>,
+    /// What the last "Repair character" changed, shown until closed.
+    pub repairs: Option<Vec<Repair>>,
     pub busy: Arc<RwLock<bool>>,
 
     pub show_about: bool,
@@ -91,11 +101,13 @@ impl AppContext {
         theme: visuals::Theme,
         meta_loader: Rc<dyn MetaLoader>,
     ) -> Self {
-        let prefix_meta = meta_loader
-            .load_prefixes()
-            .expect("Could not load prefixes");
-        let item_meta = meta_loader.load_items().expect("Could not load items");
-        let buff_meta = meta_loader.load_buffs().expect("Could not load buffs");
+        let meta = MetaBundle::new(
+            meta_loader.load_items().expect("Could not load items"),
+            meta_loader.load_buffs().expect("Could not load buffs"),
+            meta_loader
+                .load_prefixes()
+                .expect("Could not load prefixes"),
+        );
 
         Self {
             chan: (ctx, crx),
@@ -108,9 +120,7 @@ impl AppContext {
             selected_buff: SelectedBuff(0),
             selected_loadout: SelectedLoadout(0),
 
-            prefix_meta: Arc::new(RwLock::new(prefix_meta)),
-            item_meta: Arc::new(RwLock::new(item_meta)),
-            buff_meta: Arc::new(RwLock::new(buff_meta)),
+            meta: Arc::new(RwLock::new(meta)),
 
             item_spritesheet: Arc::new(RwLock::new(None)),
             buff_spritesheet: Arc::new(RwLock::new(None)),
@@ -119,8 +129,11 @@ impl AppContext {
             theme,
 
             search_term: Default::default(),
+            item_search: Default::default(),
+            item_search_hits: Vec::new(),
 
             error: None,
+            repairs: None,
             busy: Arc::new(RwLock::new(false)),
 
             show_about: false,
@@ -154,6 +167,7 @@ impl AppContext {
     pub fn is_modal_open(&self) -> bool {
         self.is_busy()
             || self.error.is_some()
+            || self.repairs.is_some()
             || self.show_about
             || self.show_item_browser
             || self.show_buff_browser
@@ -254,7 +268,7 @@ impl AppContext {
                 self.player_path = Some(path.clone());
 
                 let player = self.player.clone();
-                let item_meta = self.item_meta.clone();
+                let meta = self.meta.clone();
 
                 self.do_task(move || {
                     let mut player = player.write();
@@ -262,9 +276,9 @@ impl AppContext {
                         .extension()
                         .is_some_and(|e| e.to_string_lossy() == "dplr")
                     {
-                        player.load_decrypted(&item_meta.read(), &path)?;
+                        player.load_decrypted(&meta.read().items, &path)?;
                     } else {
-                        player.load(&item_meta.read(), &path)?;
+                        player.load(&meta.read().items, &path)?;
                     }
                     Ok(Message::Noop)
                 });
@@ -307,7 +321,7 @@ impl AppContext {
                 self.player_path = Some(path.clone());
 
                 let player = self.player.clone();
-                let item_meta = self.item_meta.clone();
+                let meta = self.meta.clone();
 
                 self.do_task(move || {
                     let player = player.read();
@@ -315,23 +329,83 @@ impl AppContext {
                         .extension()
                         .is_some_and(|e| e.to_string_lossy() == "dplr")
                     {
-                        player.save_decrypted(&item_meta.read(), &path)?;
+                        player.save_decrypted(&meta.read().items, &path)?;
                     } else {
-                        player.save(&item_meta.read(), &path)?;
+                        player.save(&meta.read().items, &path)?;
                     }
                     Ok(Message::Noop)
                 });
             }
+            Message::RepairPlayer => {
+                let repairs = self.player.write().sanitize(&self.meta.read());
+                self.repairs = Some(repairs);
+            }
+            Message::CloseRepairs => self.repairs = None,
             Message::SelectLoadout(selection) => self.selected_loadout = selection,
+            Message::EquipLoadout(index) => {
+                if let Err(err) = self.player.write().set_active_loadout(index) {
+                    self.error = Some(err.into());
+                }
+            }
+            Message::CopyLoadout { from, to } => {
+                if let Err(err) = self.player.write().copy_loadout(from, to) {
+                    self.error = Some(err.into());
+                }
+            }
+            Message::SwapLoadouts(a, b) => {
+                if let Err(err) = self.player.write().swap_loadouts(a, b) {
+                    self.error = Some(err.into());
+                }
+            }
+            Message::ExportLoadout(index) => {
+                let file_name = format!(
+                    "{}_loadout_{}.json",
+                    self.player.read().name.replace(' ', "_"),
+                    index + 1
+                );
+
+                let Some(path) = rfd::FileDialog::new()
+                    .set_file_name(file_name)
+                    .add_filter("Loadout", &["json"])
+                    .add_filter("All Files", &["*"])
+                    .save_file()
+                else {
+                    return;
+                };
+
+                let player = self.player.clone();
+
+                self.do_task(move || {
+                    player.read().export_loadout(index)?.save_json(&path)?;
+                    Ok(Message::Noop)
+                });
+            }
+            Message::ImportLoadout(index) => {
+                let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Loadout", &["json"])
+                    .add_filter("All Files", &["*"])
+                    .pick_file()
+                else {
+                    return;
+                };
+
+                let player = self.player.clone();
+
+                self.do_task(move || {
+                    let loadout = Loadout::load_json(&path)?;
+                    player.write().import_loadout(index, &loadout)?;
+                    Ok(Message::Noop)
+                });
+            }
             Message::SelectItem(selection) => self.selected_item = selection,
             Message::SelectBuff(selection) => self.selected_buff = selection,
             Message::AddAllResearch => {
                 let mut player = self.player.write();
-                let item_meta = self.item_meta.read();
+                let meta = self.meta.read();
 
                 // TODO: Maybe remove this at some point?
                 player.research.clear();
-                for item in &*item_meta {
+                for item in meta.items.iter() {
                     if item.forbidden.is_none() {
                         player.research.push(ResearchItem {
                             internal_name: item.internal_name.clone(),
@@ -348,7 +422,7 @@ impl AppContext {
                 let mut player = self.player.write();
 
                 // TODO: Maybe add `id` onto ResearchItem?
-                if let Some(meta) = self.item_meta.read().iter().find(|i| i.id == id) {
+                if let Some(meta) = self.meta.read().items.get(id) {
                     if let Some(index) = player
                         .research
                         .iter()
@@ -385,7 +459,7 @@ impl AppContext {
             }
             Message::SetCurrentItemId(id) => {
                 let player = &mut *self.player.write();
-                let selected_item = selected_item(self.selected_item, player);
+                let selected_item = selected_item_mut(self.selected_item, player);
 
                 selected_item.id = id;
 
@@ -415,7 +489,7 @@ impl AppContext {
             }
             Message::SetCurrentPrefixId(id) => {
                 let player = &mut *self.player.write();
-                let item = selected_item(self.selected_item, player);
+                let item = selected_item_mut(self.selected_item, player);
 
                 item.prefix.id = id;
 
@@ -432,6 +506,7 @@ impl AppContext {
             if self.is_modal_open() {
                 if input.consume_key(Modifiers::NONE, Key::Escape) {
                     self.error = None;
+                    self.repairs = None;
                     self.show_about = false;
                     self.show_item_browser = false;
                     self.show_buff_browser = false;
@@ -453,12 +528,34 @@ impl AppContext {
         });
     }
 
+    /// Finds the items matching `item_search`, which is done every frame so the hits follow
+    /// any changes to the player.
+    fn update_item_search(&mut self) {
+        let term = self.item_search.trim().to_lowercase();
+        if term.is_empty() {
+            self.item_search_hits.clear();
+            return;
+        }
+
+        let id = term.parse::<i32>().ok();
+        let meta = self.meta.read();
+        self.item_search_hits = self.player.read().find_items(|item| {
+            id == Some(item.id)
+                || meta
+                    .items
+                    .get(item.id)
+                    .is_some_and(|m| m.name.to_lowercase().contains(&term))
+        });
+    }
+
     pub fn update(&mut self, ctx: &egui::Context) {
         self.handle_update(ctx);
+        self.update_item_search();
         self.handle_keyboard(ctx);
 
         self.render_about(ctx);
         self.render_error(ctx);
+        self.render_repairs(ctx);
 
         self.render_item_browser(ctx);
         self.render_buff_browser(ctx);
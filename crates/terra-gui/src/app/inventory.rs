@@ -6,7 +6,7 @@ pub mod prefix_tooltip;
 pub mod slot;
 
 use egui::{Response, Ui, Vec2, Widget};
-use terra_core::{meta::Meta, utils, Buff, BuffMeta, Item, ItemMeta, Player, PrefixMeta};
+use terra_core::{meta::Meta, utils, Buff, ContainerKind, Item, Player, PrefixMeta, SlotRef};
 
 use self::{
     buff_slot::{BuffSlot, BuffSlotOptions},
@@ -40,6 +40,54 @@ pub enum ItemGroup {
     ResearchBrowser,
 }
 
+impl ItemGroup {
+    /// The player's container the group shows, or `None` for the browsers.
+    pub fn container(&self) -> Option<ContainerKind> {
+        Some(match *self {
+            Self::Inventory => ContainerKind::Inventory,
+            Self::Coins => ContainerKind::Coins,
+            Self::Ammo => ContainerKind::Ammo,
+            Self::Bank => ContainerKind::PiggyBank,
+            Self::Safe => ContainerKind::Safe,
+            Self::Forge => ContainerKind::DefendersForge,
+            Self::Void => ContainerKind::VoidVault,
+            Self::Equipment => ContainerKind::Equipment,
+            Self::EquipmentDyes => ContainerKind::EquipmentDyes,
+            Self::Armor(loadout) => ContainerKind::Armor(loadout.0),
+            Self::VanityArmor(loadout) => ContainerKind::VanityArmor(loadout.0),
+            Self::ArmorDyes(loadout) => ContainerKind::ArmorDyes(loadout.0),
+            Self::Accessories(loadout) => ContainerKind::Accessories(loadout.0),
+            Self::VanityAccessories(loadout) => ContainerKind::VanityAccessories(loadout.0),
+            Self::AccessoryDyes(loadout) => ContainerKind::AccessoryDyes(loadout.0),
+            Self::ItemBrowser | Self::ResearchBrowser => return None,
+        })
+    }
+
+    /// The group showing a container, or `None` if it isn't shown.
+    pub fn from_container(container: ContainerKind) -> Option<Self> {
+        Some(match container {
+            ContainerKind::Inventory => Self::Inventory,
+            ContainerKind::Coins => Self::Coins,
+            ContainerKind::Ammo => Self::Ammo,
+            ContainerKind::PiggyBank => Self::Bank,
+            ContainerKind::Safe => Self::Safe,
+            ContainerKind::DefendersForge => Self::Forge,
+            ContainerKind::VoidVault => Self::Void,
+            ContainerKind::Equipment => Self::Equipment,
+            ContainerKind::EquipmentDyes => Self::EquipmentDyes,
+            ContainerKind::Armor(loadout) => Self::Armor(SelectedLoadout(loadout)),
+            ContainerKind::VanityArmor(loadout) => Self::VanityArmor(SelectedLoadout(loadout)),
+            ContainerKind::ArmorDyes(loadout) => Self::ArmorDyes(SelectedLoadout(loadout)),
+            ContainerKind::Accessories(loadout) => Self::Accessories(SelectedLoadout(loadout)),
+            ContainerKind::VanityAccessories(loadout) => {
+                Self::VanityAccessories(SelectedLoadout(loadout))
+            }
+            ContainerKind::AccessoryDyes(loadout) => Self::AccessoryDyes(SelectedLoadout(loadout)),
+            ContainerKind::TemporarySlots | ContainerKind::PendingRefunds => return None,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct SelectedItem(pub ItemGroup, pub usize);
 
@@ -61,29 +109,22 @@ impl SelectedBuff {
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct SelectedLoadout(pub usize);
 
-pub fn selected_item(item: SelectedItem, player: &mut Player) -> &mut Item {
-    match item.0 {
-        ItemGroup::Inventory => &mut player.inventory[item.1],
-        ItemGroup::Coins => &mut player.coins[item.1],
-        ItemGroup::Ammo => &mut player.ammo[item.1],
-        ItemGroup::Bank => &mut player.piggy_bank[item.1],
-        ItemGroup::Safe => &mut player.safe[item.1],
-        ItemGroup::Forge => &mut player.defenders_forge[item.1],
-        ItemGroup::Void => &mut player.void_vault[item.1],
-        ItemGroup::Equipment => &mut player.equipment[item.1],
-        ItemGroup::EquipmentDyes => &mut player.equipment_dyes[item.1],
-        ItemGroup::VanityArmor(loadout) => &mut player.loadouts[loadout.0].vanity_armor[item.1],
-        ItemGroup::Armor(loadout) => &mut player.loadouts[loadout.0].armor[item.1],
-        ItemGroup::ArmorDyes(loadout) => &mut player.loadouts[loadout.0].armor_dyes[item.1],
-        ItemGroup::VanityAccessories(loadout) => {
-            &mut player.loadouts[loadout.0].vanity_accessories[item.1]
-        }
-        ItemGroup::Accessories(loadout) => &mut player.loadouts[loadout.0].accessories[item.1],
-        ItemGroup::AccessoryDyes(loadout) => &mut player.loadouts[loadout.0].accessory_dyes[item.1],
-        ItemGroup::ResearchBrowser | ItemGroup::ItemBrowser => {
-            panic!("You should never try to get the selected item of a browser")
-        }
-    }
+pub fn selected_item(item: SelectedItem, player: &Player) -> &Item {
+    let Some(container) = item.0.container() else {
+        panic!("You should never try to get the selected item of a browser")
+    };
+    SlotRef::new(container, item.1)
+        .get(player)
+        .expect("The selected item should always exist")
+}
+
+pub fn selected_item_mut(item: SelectedItem, player: &mut Player) -> &mut Item {
+    let Some(container) = item.0.container() else {
+        panic!("You should never try to get the selected item of a browser")
+    };
+    SlotRef::new(container, item.1)
+        .get_mut(player)
+        .expect("The selected item should always exist")
 }
 
 pub fn selected_buff(buff: SelectedBuff, player: &mut Player) -> &mut Buff {
@@ -130,7 +171,7 @@ impl AppContext {
     pub fn render_item_slot(&self, ui: &mut Ui, options: ItemSlotOptions) -> Response {
         let icon_spritesheet = self.icon_spritesheet.read();
         let item_spritesheet = self.item_spritesheet.read();
-        let item_meta = self.item_meta.read();
+        let all_meta = self.meta.read();
 
         if icon_spritesheet.is_none() && !self.is_busy() {
             self.send_context_msg(Message::LoadIconSpritesheet);
@@ -142,8 +183,9 @@ impl AppContext {
 
         let tooltip_options = ItemTooltipOptions::from_slot_options(options.clone());
         let tooltip_on_hover = options.tooltip_on_hover;
+        let mod_item = options.mod_item.clone();
 
-        let meta = ItemMeta::get_or_default(&item_meta, options.id);
+        let meta = all_meta.items.get_or_default(options.id);
         let slot = ItemSlot::new(
             options,
             meta,
@@ -152,7 +194,13 @@ impl AppContext {
         );
         let response = self.render_slot(ui, slot);
 
-        if meta.id != 0 && tooltip_on_hover {
+        if let (Some(mod_item), true) = (mod_item, tooltip_on_hover) {
+            response.on_hover_ui(|ui| {
+                ui.heading("Unknown mod item");
+                ui.small(mod_item);
+                ui.label("This item is kept in the tModLoader (.tplr) file");
+            })
+        } else if meta.id != 0 && tooltip_on_hover {
             response.on_hover_ui(|ui| self.render_item_tooltip(ui, tooltip_options))
         } else {
             response
@@ -166,6 +214,10 @@ impl AppContext {
         for (index, mut options) in options {
             let group = options.group;
             options.selected = self.selected_item.equals(group, index);
+            if let Some(container) = group.container() {
+                let slot = SlotRef::new(container, index);
+                options.highlighted |= self.item_search_hits.iter().any(|hit| hit.slot == slot);
+            }
 
             if self.render_item_slot(ui, options).clicked() {
                 self.send_context_msg(Message::SelectItem(SelectedItem(group, index)));
@@ -175,22 +227,21 @@ impl AppContext {
 
     pub fn render_selected_item(&mut self, ui: &mut Ui) {
         let player = &mut *self.player.write();
-        let item = selected_item(self.selected_item, player);
+        let item = selected_item_mut(self.selected_item, player);
 
-        let item_meta = self.item_meta.read();
-        let prefix_meta = self.prefix_meta.read();
+        let meta = self.meta.read();
 
-        let largest_item_id = item_meta
-            .last()
-            .expect("There should be at least one item")
-            .id;
-        let largest_prefix_id = prefix_meta
-            .last()
-            .expect("There should be at least one prefix")
-            .id;
+        let largest_item_id = meta
+            .items
+            .max_id()
+            .expect("There should be at least one item");
+        let largest_prefix_id = meta
+            .prefixes
+            .max_id()
+            .expect("There should be at least one prefix");
 
-        let item_meta = ItemMeta::get_or_default(&item_meta, item.id);
-        let prefix_meta = PrefixMeta::get(&prefix_meta, item.prefix.id);
+        let item_meta = meta.items.get_or_default(item.id);
+        let prefix_meta = meta.prefixes.get(item.prefix.id);
 
         if item.id > 0 {
             ui.label(item_name(&item_meta.name, prefix_meta));
@@ -225,14 +276,14 @@ impl AppContext {
     }
 
     pub fn render_item_tooltip(&self, ui: &mut Ui, options: ItemTooltipOptions) {
-        let item_meta = self.item_meta.read();
-        let meta = ItemMeta::get_or_default(&item_meta, options.id);
+        let all_meta = self.meta.read();
+        let meta = all_meta.items.get_or_default(options.id);
         ItemTooltip::new(options, meta).ui(ui);
     }
 
     pub fn render_buff_slot(&self, ui: &mut Ui, options: BuffSlotOptions) -> Response {
         let buff_spritesheet = self.buff_spritesheet.read();
-        let buff_meta = self.buff_meta.read();
+        let all_meta = self.meta.read();
 
         if buff_spritesheet.is_none() && !self.is_busy() {
             self.send_context_msg(Message::LoadBuffSpritesheet);
@@ -241,7 +292,7 @@ impl AppContext {
         let tooltip_options = BuffTooltipOptions::from_slot_options(&options);
         let tooltip_on_hover = options.tooltip_on_hover;
 
-        let meta = BuffMeta::get_or_default(&buff_meta, options.id);
+        let meta = all_meta.buffs.get_or_default(options.id);
         let slot = BuffSlot::new(options, meta, buff_spritesheet.as_ref());
         let response = self.render_slot(ui, slot);
 
@@ -271,14 +322,14 @@ impl AppContext {
         let player = &mut *self.player.write();
         let buff = selected_buff(self.selected_buff, player);
 
-        let buff_meta = self.buff_meta.read();
+        let meta = self.meta.read();
 
-        let largest_buff_id = buff_meta
-            .last()
-            .expect("We really should have at least one buff")
-            .id;
+        let largest_buff_id = meta
+            .buffs
+            .max_id()
+            .expect("We really should have at least one buff");
 
-        let buff_meta = BuffMeta::get_or_default(&buff_meta, buff.id);
+        let buff_meta = meta.buffs.get_or_default(buff.id);
 
         if buff.id > 0 {
             ui.label(buff_name(&buff_meta.name, Some(buff.time)));
@@ -305,15 +356,57 @@ impl AppContext {
             });
     }
 
+    pub fn render_item_search(&mut self, ui: &mut Ui) {
+        ui.label("Where is it?");
+        ui.text_edit_singleline(&mut self.item_search)
+            .on_hover_text("An item's name or id. Matching slots are highlighted.");
+
+        if self.item_search.trim().is_empty() {
+            return;
+        }
+        if self.item_search_hits.is_empty() {
+            ui.label("Not found");
+            return;
+        }
+
+        let meta = self.meta.read();
+        egui::ScrollArea::vertical()
+            .id_salt("item_search_hits")
+            .show(ui, |ui| {
+                for hit in &self.item_search_hits {
+                    let name = item_name(
+                        &meta.items.get_or_default(hit.id).name,
+                        meta.prefixes.get(hit.prefix),
+                    );
+                    let text = format!("{}: {} x{}", hit.slot, name, hit.stack);
+
+                    // Temporary slots and pending refunds aren't shown, so can't be selected
+                    let Some(group) = ItemGroup::from_container(hit.slot.container) else {
+                        ui.label(text);
+                        continue;
+                    };
+                    if ui.link(text).clicked() {
+                        if let Some(loadout) = hit.slot.container.loadout() {
+                            self.send_context_msg(Message::SelectLoadout(SelectedLoadout(loadout)));
+                        }
+                        self.send_context_msg(Message::SelectItem(SelectedItem(
+                            group,
+                            hit.slot.index,
+                        )));
+                    }
+                }
+            });
+    }
+
     pub fn render_buff_tooltip(&self, ui: &mut Ui, options: BuffTooltipOptions) {
-        let buff_meta = self.buff_meta.read();
-        let meta = BuffMeta::get_or_default(&buff_meta, options.id);
+        let all_meta = self.meta.read();
+        let meta = all_meta.buffs.get_or_default(options.id);
         BuffTooltip::new(options, meta).ui(ui)
     }
 
     pub fn render_prefix_tooltip(&self, ui: &mut Ui, options: PrefixTooltipOptions) {
-        let prefix_meta = self.prefix_meta.read();
-        let meta = PrefixMeta::get_or_default(&prefix_meta, options.id);
+        let all_meta = self.meta.read();
+        let meta = all_meta.prefixes.get_or_default(options.id);
         PrefixTooltip::new(options, meta).ui(ui);
     }
 }
@@ -59,6 +59,8 @@ pub struct ItemSlotOptions<'a> {
     pub tooltip_on_hover: bool,
     pub stack: Option<i32>,
     pub texts: Vec<SlotText>,
+    /// Name of the tModLoader item in this slot, which is shown as a placeholder.
+    pub mod_item: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -75,16 +77,19 @@ impl<'a> ItemSlotOptions<'a> {
             tooltip_on_hover: false,
             stack: None,
             texts: Vec::new(),
+            mod_item: None,
         }
     }
 
     pub fn from_item(item: &Item, group: ItemGroup) -> Self {
         let options = Self::new(group).id(item.id).favourited(item.favourited);
 
-        if item.stack > 0 {
-            options.stack(Some(item.stack))
-        } else {
-            options
+        match item.mod_item.as_deref() {
+            Some(mod_item) if !mod_item.is_vanilla() && item.id == 0 => options
+                .mod_item(Some(mod_item.display_name()))
+                .stack(Some(mod_item.stack()).filter(|s| *s > 1)),
+            _ if item.stack > 0 => options.stack(Some(item.stack)),
+            _ => options,
         }
     }
 
@@ -142,6 +147,11 @@ impl<'a> ItemSlotOptions<'a> {
         self
     }
 
+    pub fn mod_item(mut self, mod_item: Option<String>) -> Self {
+        self.mod_item = mod_item;
+        self
+    }
+
     pub fn add_text(mut self, text: SlotText) -> Self {
         self.texts.push(text);
         self
@@ -244,6 +254,13 @@ impl Widget for ItemSlot<'_> {
             };
         }
 
+        if self.options.mod_item.is_some() {
+            let font_id = TextStyle::Heading.resolve(ui.style());
+            let text_color = ui.visuals().weak_text_color();
+            let text = SlotText::new(Align2::CENTER_CENTER, "?".to_owned(), font_id, text_color);
+            self.options.texts.push(text);
+        }
+
         if let Some(stack) = self.options.stack {
             let font_id = TextStyle::Body.resolve(ui.style());
             let text_color = ui.visuals().text_color();
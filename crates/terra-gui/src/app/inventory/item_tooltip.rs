@@ -1,7 +1,9 @@
 use egui::{RichText, Ui};
-use terra_core::{utils, ItemMeta, ItemType, PrefixMeta, STRANGE_BREW_ID, STRANGE_BREW_MAX_HEAL};
+use terra_core::{
+    utils, ItemMeta, ItemStats, ItemType, PrefixMeta, STRANGE_BREW_ID, STRANGE_BREW_MAX_HEAL,
+};
 
-use super::{item_name, item_slot::ItemSlotOptions};
+use super::{item_name, item_slot::ItemSlotOptions, prefix_tooltip::modifier_lines};
 
 #[derive(Debug, Clone, Copy)]
 pub struct ItemTooltipOptions<'a> {
@@ -62,6 +64,8 @@ impl<'a> ItemTooltip<'a> {
         }
 
         let prefix = self.options.prefix_meta;
+        // Damage, speed and the like as they are with the prefix applied
+        let stats = ItemStats::new(item, prefix);
 
         ui.heading(item_name(&item.name, prefix));
         if item.forbidden.is_some_and(|f| f) {
@@ -84,7 +88,7 @@ impl<'a> ItemTooltip<'a> {
             ui.label("Quick trash, stacking, and selling will be blocked");
         }
 
-        if let Some(damage) = item.damage {
+        if let Some(damage) = stats.damage {
             let mut string = damage.to_string();
 
             if let Some(item_type) = item.item_type.as_ref() {
@@ -99,7 +103,7 @@ impl<'a> ItemTooltip<'a> {
 
             string += " damage";
 
-            if let Some(use_time) = item.use_time {
+            if let Some(use_time) = stats.use_time {
                 string += &format!(" (~{:.0} DPS)", (damage as f32) * (60. / (use_time) as f32));
             }
 
@@ -107,11 +111,11 @@ impl<'a> ItemTooltip<'a> {
         }
 
         // NOTE: Inaccuracy here: crit chance is only displayed if melee, ranged, or magic, not always
-        if let Some(crit_chance) = item.crit_chance {
+        if let Some(crit_chance) = stats.crit_chance {
             ui.label(format!("{}% critical strike chance", crit_chance));
         }
 
-        if let Some(use_time) = item.use_time {
+        if let Some(use_time) = stats.use_time {
             ui.label(format!(
                 "Use time {} ({:.02}/s, {})",
                 use_time,
@@ -120,10 +124,11 @@ impl<'a> ItemTooltip<'a> {
             ));
         }
 
-        if let Some(knockback) = item.knockback {
+        if let Some(knockback) = stats.knockback {
             ui.label(format!(
                 "Knockback {} ({})",
-                knockback,
+                // Prefixes leave knockback with float noise like 5.2500005
+                (knockback * 100.).round() / 100.,
                 utils::knockback_lookup(knockback)
             ));
         }
@@ -200,7 +205,7 @@ impl<'a> ItemTooltip<'a> {
             ui.label(format!("Restores {} mana", heal_mana));
         }
 
-        if let Some(mana_cost) = item.mana_cost {
+        if let Some(mana_cost) = stats.mana_cost {
             ui.label(format!("Uses {} mana", mana_cost));
         }
 
@@ -257,8 +262,12 @@ impl<'a> ItemTooltip<'a> {
 
         ui.label(format!("{} Max Stack", item.max_stack));
 
-        ui.label(format!("Worth {}", utils::coins_to_string(item.value)));
+        if let Some(prefix) = prefix {
+            for line in modifier_lines(prefix) {
+                ui.label(line);
+            }
+        }
 
-        // TODO: Maybe prefix values?
+        ui.label(format!("Worth {}", utils::coins_to_string(stats.value)));
     }
 }
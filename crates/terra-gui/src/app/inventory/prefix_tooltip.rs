@@ -37,5 +37,43 @@ impl<'a> PrefixTooltip<'a> {
 
         ui.heading(prefix.name.as_ref());
         ui.small(format!("Id: {}", prefix.id));
+
+        for line in modifier_lines(prefix) {
+            ui.label(line);
+        }
+    }
+}
+
+/// The prefix's modifiers as they're listed under an item's tooltip, like "+10% damage".
+pub(super) fn modifier_lines(prefix: &PrefixMeta) -> Vec<String> {
+    let percents = [
+        (prefix.damage, "damage"),
+        (prefix.use_time.map(|t| 2. - t), "speed"),
+        (prefix.knockback, "knockback"),
+        (prefix.mana_cost, "mana cost"),
+        (prefix.size, "size"),
+        (prefix.velocity, "velocity"),
+        (prefix.move_speed, "movement speed"),
+        (prefix.melee_speed, "melee speed"),
+    ];
+    let bonuses = [
+        (prefix.crit, "% critical strike chance"),
+        (prefix.defense, " defense"),
+        (prefix.mana, " mana"),
+    ];
+
+    let mut lines = Vec::new();
+    for (multiplier, stat) in percents {
+        if let Some(multiplier) = multiplier {
+            let percent = ((multiplier - 1.) * 100.).round() as i32;
+            lines.push(format!("{percent:+}% {stat}"));
+        }
     }
+    for (bonus, stat) in bonuses {
+        if let Some(bonus) = bonus {
+            lines.push(format!("{bonus:+}{stat}"));
+        }
+    }
+
+    lines
 }
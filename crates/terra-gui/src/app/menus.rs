@@ -28,6 +28,10 @@ impl App {
             ui.close_kind(UiKind::Menu);
             self.send_context_msg(Message::SavePlayer);
         }
+        if ui.button("Repair character\u{2026}").clicked() {
+            ui.close_kind(UiKind::Menu);
+            self.send_context_msg(Message::RepairPlayer);
+        }
         if ui.shortcut_button("Exit", &SHORTCUT_EXIT).clicked() {
             ui.close_kind(UiKind::Menu);
             self.send_app_msg(AppMessage::Exit);
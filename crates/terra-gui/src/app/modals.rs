@@ -8,7 +8,7 @@ use super::{
         buff_slot::{self, BuffSlotOptions},
         item_slot::{self, ItemSlotOptions},
         prefix_tooltip::PrefixTooltipOptions,
-        ItemGroup,
+        selected_item, ItemGroup,
     },
     Message, EGUI_GITHUB_REPO_NAME, EGUI_GITHUB_REPO_URL, GITHUB_REPO_NAME, GITHUB_REPO_URL,
 };
@@ -155,6 +155,43 @@ impl AppContext {
         );
     }
 
+    pub fn render_repairs(&self, ctx: &egui::Context) {
+        let Some(repairs) = self.repairs.as_ref() else {
+            return;
+        };
+
+        self.render_modal(
+            ctx,
+            "Repair Character",
+            false,
+            Sizing::Fixed(vec2(ERROR_MODAL_WIDTH, ERROR_MODAL_HEIGHT)),
+            |ui| {
+                ui.spacing_mut().item_spacing.y = 8.;
+
+                if repairs.is_empty() {
+                    ui.label("Nothing needed repairing.");
+                } else {
+                    ui.label(format!("Made {} repair(s):", repairs.len()));
+
+                    ScrollArea::vertical()
+                        .id_salt("repairs_scrollarea")
+                        .max_height(ERROR_MODAL_HEIGHT - 64.)
+                        .show(ui, |ui| {
+                            for repair in repairs {
+                                ui.label(repair.to_string());
+                            }
+                        });
+                }
+
+                ui.vertical_right_justified(|ui| {
+                    if ui.button("Ok").clicked() {
+                        self.send_context_msg(Message::CloseRepairs);
+                    }
+                });
+            },
+        );
+    }
+
     pub fn render_item_browser(&mut self, ctx: &egui::Context) {
         if self.show_item_browser {
             let mut search_term = self.search_term.clone();
@@ -169,7 +206,8 @@ impl AppContext {
 
                 if !search_term.is_empty() {
                     let search_term_lower = search_term.to_lowercase();
-                    let meta = &self.item_meta.read();
+                    let all_meta = self.meta.read();
+                    let meta = &all_meta.items;
                     let filtered = meta
                         .iter()
                         .filter(|meta| meta.name.to_lowercase().contains(&search_term_lower))
@@ -245,7 +283,8 @@ impl AppContext {
 
                 if !search_term.is_empty() {
                     let search_term_lower = search_term.to_lowercase();
-                    let meta = &self.buff_meta.read();
+                    let all_meta = self.meta.read();
+                    let meta = &all_meta.buffs;
                     let filtered = meta
                         .iter()
                         .filter(|meta| meta.name.to_lowercase().contains(&search_term_lower));
@@ -301,12 +340,11 @@ impl AppContext {
         }
     }
 
-    // TODO: Add a toggle to show only class-specific prefixes
-    //       (and the Terrarian variant of Legendary)
     pub fn render_prefix_browser(&mut self, ctx: &egui::Context) {
         if self.show_prefix_browser {
             let mut search_term = self.search_term.clone();
             let mut term_changed = false;
+            let item_id = selected_item(self.selected_item, &self.player.read()).id;
 
             self.render_modal(
                 ctx,
@@ -320,11 +358,18 @@ impl AppContext {
                         term_changed = ui.text_edit_singleline(&mut search_term).changed();
                     });
 
-                    if !search_term.is_empty() {
+                    let all_meta = self.meta.read();
+                    // Without an item to check against, every prefix is offered
+                    let item_meta = all_meta.items.get(item_id).filter(|m| m.id != 0);
+
+                    if !search_term.is_empty() || item_meta.is_some() {
                         let search_term_lower = search_term.to_lowercase();
-                        let meta = &self.prefix_meta.read();
+                        let meta = &all_meta.prefixes;
                         let filtered = meta
                             .iter()
+                            .filter(|meta| {
+                                meta.id == 0 || item_meta.is_none_or(|item| meta.is_valid_for(item))
+                            })
                             .filter(|meta| meta.name.to_lowercase().contains(&search_term_lower));
 
                         let total_rows = ((filtered.clone().count() as f32)
@@ -408,7 +453,8 @@ impl AppContext {
                     });
 
                     let search_term_lower = search_term.to_lowercase();
-                    let meta = &self.item_meta.read();
+                    let all_meta = self.meta.read();
+                    let meta = &all_meta.items;
                     let filtered = meta
                         .iter()
                         .filter(|meta| {
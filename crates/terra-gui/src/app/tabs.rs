@@ -1,10 +1,10 @@
 use std::fmt::Display;
 
-use egui::{Align2, ComboBox, TextStyle, Ui, WidgetText};
+use egui::{Align2, Button, ComboBox, TextStyle, Ui, UiKind, WidgetText};
 use egui_dock::{DockState, TabViewer};
 
 use terra_core::{
-    meta::Meta,
+    meta::MetaRegistry,
     utils::{self},
     Difficulty, Item, PrefixMeta, Team, ARMOR_COUNT, BANK_STRIDE, BUFF_STRIDE, HAIR_DYE_COUNT,
     HAIR_STYLE_COUNT, INVENTORY_STRIDE, LOADOUT_COUNT, SKIN_VARIANT_COUNT,
@@ -341,13 +341,15 @@ impl AppContext {
         self.render_selected_item(ui);
         ui.separator();
         self.render_selected_buff(ui);
+        ui.separator();
+        self.render_item_search(ui);
     }
 
     fn render_item_tab<F>(
         &self,
         ui: &mut Ui,
         options: ItemTabOptions,
-        prefix_meta: &[PrefixMeta],
+        prefix_meta: &MetaRegistry<PrefixMeta>,
         items: &[Item],
         extra_cols: F,
     ) where
@@ -383,7 +385,7 @@ impl AppContext {
                                 (
                                     index,
                                     ItemSlotOptions::from_item(item, options.group)
-                                        .prefix_meta(PrefixMeta::get(prefix_meta, item.prefix.id))
+                                        .prefix_meta(prefix_meta.get(item.prefix.id))
                                         .tooltip_on_hover(true),
                                 )
                             });
@@ -400,7 +402,8 @@ impl AppContext {
 
     fn render_inventory_tab(&mut self, ui: &mut Ui) {
         let player = self.player.read();
-        let prefix_meta = self.prefix_meta.read();
+        let meta = self.meta.read();
+        let prefix_meta = &meta.prefixes;
 
         const EXTRA_STRIDE: usize = 2;
 
@@ -413,7 +416,7 @@ impl AppContext {
                 5,
                 INVENTORY_STRIDE,
             ),
-            &prefix_meta,
+            prefix_meta,
             &player.inventory,
             |ui, row| {
                 if row < 4 {
@@ -424,12 +427,12 @@ impl AppContext {
                         (
                             row,
                             ItemSlotOptions::from_item(coins, ItemGroup::Coins)
-                                .prefix_meta(PrefixMeta::get(&prefix_meta, coins.prefix.id)),
+                                .prefix_meta(prefix_meta.get(coins.prefix.id)),
                         ),
                         (
                             row,
                             ItemSlotOptions::from_item(ammo, ItemGroup::Ammo)
-                                .prefix_meta(PrefixMeta::get(&prefix_meta, ammo.prefix.id)),
+                                .prefix_meta(prefix_meta.get(ammo.prefix.id)),
                         ),
                     ]
                     .into_iter()
@@ -443,12 +446,13 @@ impl AppContext {
 
     fn render_bank_tab(&mut self, ui: &mut Ui) {
         let player = self.player.read();
-        let prefix_meta = self.prefix_meta.read();
+        let meta = self.meta.read();
+        let prefix_meta = &meta.prefixes;
 
         self.render_item_tab(
             ui,
             ItemTabOptions::new("player_bank", ItemGroup::Bank, BANK_STRIDE, 4, BANK_STRIDE),
-            &prefix_meta,
+            prefix_meta,
             &player.piggy_bank,
             |_, _| {},
         );
@@ -456,12 +460,13 @@ impl AppContext {
 
     fn render_safe_tab(&mut self, ui: &mut Ui) {
         let player = self.player.read();
-        let prefix_meta = self.prefix_meta.read();
+        let meta = self.meta.read();
+        let prefix_meta = &meta.prefixes;
 
         self.render_item_tab(
             ui,
             ItemTabOptions::new("player_safe", ItemGroup::Safe, BANK_STRIDE, 4, BANK_STRIDE),
-            &prefix_meta,
+            prefix_meta,
             &player.safe,
             |_, _| {},
         );
@@ -469,7 +474,8 @@ impl AppContext {
 
     fn render_forge_tab(&mut self, ui: &mut Ui) {
         let player = self.player.read();
-        let prefix_meta = self.prefix_meta.read();
+        let meta = self.meta.read();
+        let prefix_meta = &meta.prefixes;
 
         self.render_item_tab(
             ui,
@@ -480,7 +486,7 @@ impl AppContext {
                 4,
                 BANK_STRIDE,
             ),
-            &prefix_meta,
+            prefix_meta,
             &player.defenders_forge,
             |_, _| {},
         );
@@ -488,12 +494,13 @@ impl AppContext {
 
     fn render_void_tab(&mut self, ui: &mut Ui) {
         let player = self.player.read();
-        let prefix_meta = self.prefix_meta.read();
+        let meta = self.meta.read();
+        let prefix_meta = &meta.prefixes;
 
         self.render_item_tab(
             ui,
             ItemTabOptions::new("player_void", ItemGroup::Void, BANK_STRIDE, 4, BANK_STRIDE),
-            &prefix_meta,
+            prefix_meta,
             &player.void_vault,
             |_, _| {},
         );
@@ -546,7 +553,8 @@ impl AppContext {
 
     fn render_equipment_tab(&mut self, ui: &mut Ui) {
         let player = self.player.read();
-        let prefix_meta = self.prefix_meta.read();
+        let meta = self.meta.read();
+        let prefix_meta = &meta.prefixes;
 
         const EQUIPMENT_ICONS: [ItemSlotIcon; 5] = [
             ItemSlotIcon::Pet,
@@ -604,19 +612,13 @@ impl AppContext {
                                 i,
                                 ItemSlotOptions::from_item(equipment_dye, ItemGroup::EquipmentDyes)
                                     .icon(Some(ItemSlotIcon::Dye))
-                                    .prefix_meta(PrefixMeta::get(
-                                        &prefix_meta,
-                                        equipment_dye.prefix.id,
-                                    )),
+                                    .prefix_meta(prefix_meta.get(equipment_dye.prefix.id)),
                             ),
                             (
                                 i,
                                 ItemSlotOptions::from_item(equipment, ItemGroup::Equipment)
                                     .icon(Some(EQUIPMENT_ICONS[i]))
-                                    .prefix_meta(PrefixMeta::get(
-                                        &prefix_meta,
-                                        equipment.prefix.id,
-                                    )),
+                                    .prefix_meta(prefix_meta.get(equipment.prefix.id)),
                             ),
                             (
                                 i,
@@ -625,10 +627,7 @@ impl AppContext {
                                     ItemGroup::AccessoryDyes(self.selected_loadout),
                                 )
                                 .icon(Some(ItemSlotIcon::Dye))
-                                .prefix_meta(PrefixMeta::get(
-                                    &prefix_meta,
-                                    accessory_dye.prefix.id,
-                                )),
+                                .prefix_meta(prefix_meta.get(accessory_dye.prefix.id)),
                             ),
                             (
                                 i,
@@ -637,10 +636,7 @@ impl AppContext {
                                     ItemGroup::VanityAccessories(self.selected_loadout),
                                 )
                                 .icon(Some(ItemSlotIcon::VanityAccessory))
-                                .prefix_meta(PrefixMeta::get(
-                                    &prefix_meta,
-                                    vanity_accessory.prefix.id,
-                                )),
+                                .prefix_meta(prefix_meta.get(vanity_accessory.prefix.id)),
                             ),
                             (
                                 i,
@@ -649,7 +645,7 @@ impl AppContext {
                                     ItemGroup::Accessories(self.selected_loadout),
                                 )
                                 .icon(Some(ItemSlotIcon::Accessory))
-                                .prefix_meta(PrefixMeta::get(&prefix_meta, accessory.prefix.id)),
+                                .prefix_meta(prefix_meta.get(accessory.prefix.id)),
                             ),
                         ]
                         .into_iter()
@@ -670,9 +666,7 @@ impl AppContext {
                                         ItemGroup::ArmorDyes(self.selected_loadout),
                                     )
                                     .icon(Some(ItemSlotIcon::Dye))
-                                    .prefix_meta(
-                                        PrefixMeta::get(&prefix_meta, armor_dye.prefix.id),
-                                    ),
+                                    .prefix_meta(prefix_meta.get(armor_dye.prefix.id)),
                                 ),
                                 (
                                     i,
@@ -681,9 +675,7 @@ impl AppContext {
                                         ItemGroup::VanityArmor(self.selected_loadout),
                                     )
                                     .icon(Some(VANITY_ARMOR_ICONS[i]))
-                                    .prefix_meta(
-                                        PrefixMeta::get(&prefix_meta, vanity_armor.prefix.id),
-                                    ),
+                                    .prefix_meta(prefix_meta.get(vanity_armor.prefix.id)),
                                 ),
                                 (
                                     i,
@@ -692,7 +684,7 @@ impl AppContext {
                                         ItemGroup::Armor(self.selected_loadout),
                                     )
                                     .icon(Some(ARMOR_ICONS[i]))
-                                    .prefix_meta(PrefixMeta::get(&prefix_meta, armor.prefix.id)),
+                                    .prefix_meta(prefix_meta.get(armor.prefix.id)),
                                 ),
                             ]
                             .into_iter()
@@ -714,9 +706,7 @@ impl AppContext {
                                         ItemGroup::AccessoryDyes(self.selected_loadout),
                                     )
                                     .icon(Some(ItemSlotIcon::Dye))
-                                    .prefix_meta(
-                                        PrefixMeta::get(&prefix_meta, accessory_dye.prefix.id),
-                                    ),
+                                    .prefix_meta(prefix_meta.get(accessory_dye.prefix.id)),
                                 ),
                                 (
                                     i,
@@ -725,9 +715,7 @@ impl AppContext {
                                         ItemGroup::VanityAccessories(self.selected_loadout),
                                     )
                                     .icon(Some(ItemSlotIcon::VanityAccessory))
-                                    .prefix_meta(
-                                        PrefixMeta::get(&prefix_meta, vanity_accessory.prefix.id),
-                                    ),
+                                    .prefix_meta(prefix_meta.get(vanity_accessory.prefix.id)),
                                 ),
                                 (
                                     i,
@@ -736,9 +724,7 @@ impl AppContext {
                                         ItemGroup::Accessories(self.selected_loadout),
                                     )
                                     .icon(Some(ItemSlotIcon::Accessory))
-                                    .prefix_meta(
-                                        PrefixMeta::get(&prefix_meta, accessory.prefix.id),
-                                    ),
+                                    .prefix_meta(prefix_meta.get(accessory.prefix.id)),
                                 ),
                             ]
                             .into_iter()
@@ -762,17 +748,70 @@ impl AppContext {
                             self.render_item_slots(ui, options);
                         }
 
-                        if i == 0 {
-                            let mut loadout = self.selected_loadout;
-
-                            if ComboBox::from_id_salt("player_loadouts")
-                                .show_index(ui, &mut loadout.0, LOADOUT_COUNT, |i| {
-                                    format!("Loadout {}", i + 1)
-                                })
-                                .changed()
-                            {
-                                self.send_context_msg(Message::SelectLoadout(loadout));
+                        let selected = self.selected_loadout.0;
+                        let equipped = player.current_loadout_index as usize;
+                        let others = (0..LOADOUT_COUNT).filter(|&j| j != selected);
+
+                        match i {
+                            0 => {
+                                let mut loadout = self.selected_loadout;
+
+                                if ComboBox::from_id_salt("player_loadouts")
+                                    .show_index(ui, &mut loadout.0, LOADOUT_COUNT, |i| {
+                                        if i == equipped {
+                                            format!("Loadout {} (equipped)", i + 1)
+                                        } else {
+                                            format!("Loadout {}", i + 1)
+                                        }
+                                    })
+                                    .changed()
+                                {
+                                    self.send_context_msg(Message::SelectLoadout(loadout));
+                                }
+                            }
+                            1 => {
+                                let equip =
+                                    ui.add_enabled(selected != equipped, Button::new("Equip"));
+                                if equip.clicked() {
+                                    self.send_context_msg(Message::EquipLoadout(selected));
+                                }
                             }
+                            2 => {
+                                ui.menu_button("Copy to", |ui| {
+                                    for to in others {
+                                        if ui.button(format!("Loadout {}", to + 1)).clicked() {
+                                            self.send_context_msg(Message::CopyLoadout {
+                                                from: selected,
+                                                to,
+                                            });
+                                            ui.close_kind(UiKind::Menu);
+                                        }
+                                    }
+                                });
+                            }
+                            3 => {
+                                ui.menu_button("Swap with", |ui| {
+                                    for other in others {
+                                        if ui.button(format!("Loadout {}", other + 1)).clicked() {
+                                            self.send_context_msg(Message::SwapLoadouts(
+                                                selected, other,
+                                            ));
+                                            ui.close_kind(UiKind::Menu);
+                                        }
+                                    }
+                                });
+                            }
+                            4 => {
+                                ui.horizontal(|ui| {
+                                    if ui.button("Export").clicked() {
+                                        self.send_context_msg(Message::ExportLoadout(selected));
+                                    }
+                                    if ui.button("Import").clicked() {
+                                        self.send_context_msg(Message::ImportLoadout(selected));
+                                    }
+                                });
+                            }
+                            _ => {}
                         }
 
                         ui.end_row();
@@ -782,18 +821,18 @@ impl AppContext {
     }
 
     fn render_research_tab(&mut self, ui: &mut Ui) {
-        let player = self.player.read();
-        let entry_text = if player.research.len() == 1 {
-            "item"
-        } else {
-            "items"
+        let (research_count, version, mut share_research) = {
+            let player = self.player.read();
+            (player.research.len(), player.version, player.share_research)
         };
+        let entry_text = if research_count == 1 { "item" } else { "items" };
+
+        ui.label(format!("{research_count} researched {entry_text}"));
 
-        ui.label(format!(
-            "{} researched {}",
-            player.research.len(),
-            entry_text
-        ));
+        let share_checkbox = egui::Checkbox::new(&mut share_research, "Share research with team");
+        if ui.add_enabled(version >= 282, share_checkbox).changed() {
+            self.player.write().share_research = share_research;
+        }
 
         ui.horizontal(|ui| {
             if ui.button("Clear all").clicked() {
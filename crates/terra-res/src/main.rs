@@ -17,7 +17,10 @@ use mlua::Lua;
 use regex::{Captures, Regex};
 
 use reqwest::{blocking::Client, header::HeaderMap};
-use terra_core::{BuffMeta, BuffType, ItemMeta, ItemRarity, ItemType, PrefixMeta, SharedString};
+use terra_core::{
+    BuffMeta, BuffType, ItemMeta, ItemRarity, ItemType, LegacyName, PrefixCategory, PrefixMeta,
+    SharedString,
+};
 
 mod truthy;
 use truthy::TruthyOption;
@@ -25,6 +28,7 @@ use truthy::TruthyOption;
 const ITEM_DATA_URL: &str = "https://terraria.wiki.gg/api.php?action=query&prop=revisions&format=json&rvlimit=1&rvslots=*&rvprop=content&titles=Module:Iteminfo/luadata";
 const BUFF_INFO_URL:&str = "https://terraria.wiki.gg/api.php?action=query&prop=revisions&format=json&rvlimit=1&rvslots=*&rvprop=content&titles=Template:GetBuffInfo";
 const PREFIX_IDS_URL: &str = "https://terraria.wiki.gg/api.php?action=query&prop=revisions&format=json&rvlimit=1&rvslots=*&rvprop=content&titles=Prefix_IDs";
+const MODIFIERS_URL: &str = "https://terraria.wiki.gg/api.php?action=query&prop=revisions&format=json&rvlimit=1&rvslots=*&rvprop=content&titles=Modifiers";
 const TRAPPED_CHEST_DOT: &str = "trapped_chest_dot.png";
 
 fn get_offsets(path: &Path) -> Result<HashMap<i32, [i32; 4]>> {
@@ -252,6 +256,33 @@ fn trapped_chests() -> HashMap<i32, i32> {
     map
 }
 
+/// Items that were renamed before players started saving items by id (version 38), along
+/// with the last version that saved them under their old name.
+///
+/// This is every rename the game itself applies to old names, in `Item.VersionName`. The wiki
+/// doesn't list them, so they're kept here by hand.
+fn legacy_names() -> HashMap<i32, Vec<LegacyName>> {
+    let mut map = HashMap::new();
+    let mut insert = |id: i32, name: &str, until: i32| {
+        map.entry(id).or_insert_with(Vec::new).push(LegacyName {
+            name: SharedString::new(name),
+            until,
+        })
+    };
+    insert(228, "Cobalt Helmet", 4);
+    insert(229, "Cobalt Breastplate", 4);
+    insert(230, "Cobalt Greaves", 4);
+    insert(331, "Jungle Rose", 13);
+    insert(291, "Gills potion", 20);
+    insert(191, "Thorn Chakrum", 20);
+    insert(162, "Ball 'O Hurt", 20);
+    insert(85, "Iron Chain", 41);
+    insert(115, "Orb of Light", 44);
+    insert(254, "Black Dye", 46);
+    insert(255, "Green Dye", 46);
+    map
+}
+
 fn forbidden_items() -> Vec<i32> {
     let mut forbidden_items = Vec::new();
     forbidden_items.push(0);
@@ -293,6 +324,35 @@ fn forbidden_items() -> Vec<i32> {
     forbidden_items
 }
 
+/// Melee weapons the game doesn't give melee prefixes to, as they aren't swung.
+///
+/// This is copied by hand from `PrefixLegacy.ItemSets` in the game's source. The game picks
+/// an item's prefix pool from these hard-coded sets rather than from anything in the item's
+/// defaults, so the wiki's item data has nothing to scrape it from.
+fn projectile_melee_items() -> Vec<i32> {
+    let mut projectile_melee_items = Vec::new();
+    // Boomerangs and thrown weapons
+    projectile_melee_items.extend([
+        55, 119, 191, 284, 561, 670, 1122, 1324, 1513, 1569, 1571, 1825, 1918, 3030, 3054, 4764,
+        4818, 5298,
+    ]);
+    // Flails
+    projectile_melee_items.extend([
+        162, 163, 220, 389, 801, 1259, 1297, 1314, 1325, 2424, 2611, 3012, 3473, 4272, 5011, 5012,
+    ]);
+    // Spears and lances
+    projectile_melee_items.extend([
+        274, 277, 280, 390, 406, 537, 550, 756, 802, 1186, 1193, 1200, 1228, 1947, 2331, 2332,
+        3543, 3836, 4061, 4788, 4789, 4790, 5094, 5687,
+    ]);
+    // Yoyos
+    projectile_melee_items.extend([
+        3262, 3278, 3279, 3280, 3281, 3282, 3283, 3284, 3285, 3286, 3287, 3288, 3289, 3290, 3291,
+        3292, 3315, 3316, 3317, 3389, 5294,
+    ]);
+    projectile_melee_items
+}
+
 fn expand_templates(
     s: &str,
     template: &Regex,
@@ -418,6 +478,8 @@ fn get_item_meta(
 
     let offsets = get_offsets(offset_filepath)?;
     let forbidden_items = forbidden_items();
+    let projectile_melee_items = projectile_melee_items();
+    let mut legacy_names = legacy_names();
 
     let lua = Lua::new();
     let info: mlua::Table = lua.load(lua_str).set_name("Iteminfo_luadata").eval()?;
@@ -473,6 +535,12 @@ fn get_item_meta(
             None
         };
 
+        let is_projectile_melee = if projectile_melee_items.contains(&id) {
+            Some(true)
+        } else {
+            None
+        };
+
         let consumes_tile = lua_item.get("tileWand").truthy_option();
 
         let item_type = get_item_type(&lua_item);
@@ -531,6 +599,8 @@ fn get_item_meta(
             is_consumable,
             is_quest_item,
             is_expert,
+            is_projectile_melee,
+            legacy_names: legacy_names.remove(&id),
         };
 
         item_meta.push(item);
@@ -683,6 +753,188 @@ fn get_buff_meta(
     Ok(buff_meta)
 }
 
+fn prefix(category: PrefixCategory) -> PrefixMeta {
+    PrefixMeta {
+        id: 0,
+        name: SharedString::default(),
+        internal_name: SharedString::default(),
+        category: Some(category),
+        damage: None,
+        use_time: None,
+        crit: None,
+        knockback: None,
+        mana_cost: None,
+        size: None,
+        velocity: None,
+        defense: None,
+        mana: None,
+        move_speed: None,
+        melee_speed: None,
+    }
+}
+
+fn multiplier(value: f32) -> Option<f32> {
+    (value != 1.).then_some(value)
+}
+
+fn bonus(value: i32) -> Option<i32> {
+    (value != 0).then_some(value)
+}
+
+/// The category of the prefixes listed under a heading of the Modifiers page, if any.
+fn prefix_category(heading: &str) -> Option<PrefixCategory> {
+    use PrefixCategory::*;
+
+    let heading = heading.to_lowercase();
+    [
+        ("universal", Universal),
+        ("common", Common),
+        ("melee", Melee),
+        ("ranged", Ranged),
+        ("magic", Magic),
+        ("accessor", Accessory),
+    ]
+    .into_iter()
+    .find(|(name, _)| heading.contains(name))
+    .map(|(_, category)| category)
+}
+
+/// Sets the stat a column of the Modifiers page is for, from a value as the wiki shows it
+/// (`-10` for "-10%"). Columns for other things, like the tier or value, are ignored.
+fn set_prefix_stat(prefix: &mut PrefixMeta, column: &str, value: f32) {
+    let percent = (100. + value) / 100.;
+
+    if column.contains("defense") {
+        prefix.defense = bonus(value as i32);
+    } else if column.contains("crit") {
+        prefix.crit = bonus(value as i32);
+    } else if column.contains("damage") {
+        prefix.damage = multiplier(percent);
+    } else if column.contains("knockback") {
+        prefix.knockback = multiplier(percent);
+    } else if column.contains("size") {
+        prefix.size = multiplier(percent);
+    } else if column.contains("velocity") {
+        prefix.velocity = multiplier(percent);
+    } else if column.contains("mana") {
+        // Accessories add max mana, weapons change their mana cost
+        if prefix.category == Some(PrefixCategory::Accessory) {
+            prefix.mana = bonus(value as i32);
+        } else {
+            prefix.mana_cost = multiplier(percent);
+        }
+    } else if column.contains("movement") {
+        prefix.move_speed = multiplier(percent);
+    } else if column.contains("melee speed") {
+        prefix.melee_speed = multiplier(percent);
+    } else if column.contains("speed") {
+        // The wiki shows how much faster a weapon is used, so +10% is 0.9x the use time
+        prefix.use_time = multiplier((100. - value) / 100.);
+    }
+}
+
+/// A table cell's content, without any attributes before it (`style="..." | content`).
+fn cell_content(cell: &str) -> String {
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in cell.char_indices() {
+        match c {
+            '{' | '[' => depth += 1,
+            '}' | ']' => depth -= 1,
+            '|' if depth == 0 => start = i + 1,
+            _ => {}
+        }
+    }
+    cell[start..].trim().to_owned()
+}
+
+/// The wikitext of the only page a query returns.
+fn page_text(client: &Client, url: &str) -> Result<String> {
+    let resp = client.get(url).send()?;
+    let resp = resp.error_for_status()?;
+    let json: serde_json::Value = resp.json()?;
+
+    let text = json["query"]["pages"]
+        .as_object()
+        .and_then(|pages| pages.values().next())
+        .and_then(|page| page["revisions"][0]["slots"]["main"]["*"].as_str())
+        .expect("No page text in the response");
+
+    Ok(text.to_owned())
+}
+
+/// Reads the prefixes' stats from the tables on the wiki's Modifiers page, which has a
+/// section for each category and a column for each stat. Rows are matched to `names`, and
+/// names listed more than once (like the Terrarian's Legendary) keep each row in page order.
+fn get_prefix_stats(client: &Client, names: &[String]) -> Result<HashMap<String, Vec<PrefixMeta>>> {
+    let text = page_text(client, MODIFIERS_URL)?;
+
+    let heading_regex = Regex::new(r"^=+\s*(.*?)\s*=+$")?;
+    let markup_regex = Regex::new(r"<ref[^>]*/>|<ref.*?</ref>|<[^>]*>")?;
+    let number_regex = Regex::new(r"([+\-−–]?)\s*(\d+(?:\.\d+)?)")?;
+
+    let mut stats: HashMap<String, Vec<PrefixMeta>> = HashMap::new();
+    let mut category = None;
+    let mut columns: Vec<String> = Vec::new();
+    let mut in_header = false;
+    let mut cells: Vec<String> = Vec::new();
+
+    let split_cells = |line: &str, separator: &str| {
+        line.split(separator)
+            .map(|cell| cell_content(&markup_regex.replace_all(cell, "")))
+            .collect_vec()
+    };
+
+    for line in text.lines().map(str::trim) {
+        if let Some(cap) = heading_regex.captures(line) {
+            category = prefix_category(&cap[1]);
+        } else if line.starts_with("{|") {
+            columns.clear();
+            in_header = true;
+        } else if line.starts_with("|-") || line.starts_with("|}") {
+            in_header &= columns.is_empty();
+
+            // Modifier names are usually in a template, so they're found by what they contain
+            let name = cells.first().and_then(|cell| {
+                names
+                    .iter()
+                    .filter(|name| cell.contains(name.as_str()))
+                    .max_by_key(|name| name.len())
+            });
+
+            if let (Some(category), Some(name)) = (category, name) {
+                let mut prefix = prefix(category);
+                for (column, cell) in columns.iter().zip(&cells).skip(1) {
+                    let Some(cap) = number_regex.captures(cell) else {
+                        continue;
+                    };
+                    let value = cap[2].parse::<f32>()?;
+                    let value = if matches!(&cap[1], "-" | "−" | "–") {
+                        -value
+                    } else {
+                        value
+                    };
+                    set_prefix_stat(&mut prefix, &column.to_lowercase(), value);
+                }
+                stats.entry(name.clone()).or_default().push(prefix);
+            }
+
+            cells.clear();
+        } else if let Some(header) = line.strip_prefix('!') {
+            // Rows can start with a header cell for the modifier's name
+            if in_header {
+                columns.extend(split_cells(header, "!!"));
+            } else {
+                cells.extend(split_cells(header, "!!"));
+            }
+        } else if let Some(row) = line.strip_prefix('|') {
+            cells.extend(split_cells(row, "||"));
+        }
+    }
+
+    Ok(stats)
+}
+
 fn get_prefix_meta(
     client: &Client,
     _template: &Regex,
@@ -719,7 +971,7 @@ fn get_prefix_meta(
     let prefix_regex = Regex::new(r"\|\s*(\d+)\s*\|\|\s*\{\{gameText\|Prefix\.([^\{}]*)\}\}")?;
     let mut prefix_captures = prefix_regex.captures_iter(text);
 
-    let mut prefix_meta: Vec<PrefixMeta> = Vec::new();
+    let mut prefixes = Vec::new();
 
     loop {
         let Some(cap) = prefix_captures.next() else {
@@ -737,10 +989,30 @@ fn get_prefix_meta(
                 .to_owned()
         };
 
+        prefixes.push((id, internal_name.to_owned(), name));
+    }
+
+    let names = prefixes
+        .iter()
+        .map(|(_, _, name)| name.clone())
+        .unique()
+        .collect_vec();
+    let mut prefix_stats = get_prefix_stats(client, &names)?;
+
+    let mut prefix_meta: Vec<PrefixMeta> = Vec::new();
+
+    for (id, internal_name, name) in prefixes {
+        let stats = prefix_stats
+            .get_mut(&name)
+            .filter(|stats| !stats.is_empty())
+            .map(|stats| stats.remove(0))
+            .unwrap_or_else(|| panic!("No stats for prefix {id} ({name}) on the Modifiers page"));
+
         prefix_meta.push(PrefixMeta {
             id,
-            internal_name: SharedString::new(internal_name),
+            internal_name: SharedString::new(&internal_name),
             name: SharedString::new(&name),
+            ..stats
         });
     }
 
@@ -750,6 +1022,8 @@ fn get_prefix_meta(
         id: 0,
         name: SharedString::default(),
         internal_name: SharedString::new("None"),
+        category: None,
+        ..prefix(PrefixCategory::Universal)
     });
 
     prefix_meta.sort_by_key(|p| p.id);
@@ -1,6 +1,9 @@
 use std::{fs::File, io::Read, path::PathBuf};
 
-use terra_core::{BuffMeta, ItemMeta, Player, PrefixMeta};
+use jsonschema::Validator;
+use terra_core::{
+    interchange::PLAYER_SCHEMA, BuffMeta, ItemMeta, MetaBundle, Player, PlayerFormat, PrefixMeta,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum TestError {
@@ -10,6 +13,8 @@ pub enum TestError {
     Save(anyhow::Error),
     #[error("Error during comparison")]
     Comparison,
+    #[error("Error during JSON export or import")]
+    Interchange(anyhow::Error),
 }
 
 const VERSIONS: [usize; 19] = [
@@ -19,8 +24,10 @@ const VERSIONS: [usize; 19] = [
 fn run_test(
     chara_name: &String,
     directory: &PathBuf,
-    item_meta: &Vec<ItemMeta>,
+    meta: &MetaBundle,
+    schema: &Validator,
 ) -> anyhow::Result<(), TestError> {
+    let item_meta = &meta.items;
     let filepath = directory.join(format!("{}.plr", chara_name));
 
     println!("Filepath: {}", filepath.display());
@@ -79,6 +86,39 @@ fn run_test(
         }
     }
 
+    let old_bytes = std::fs::read(&filepath).expect("Could not read old file");
+
+    let mem_plr = match Player::from_bytes(item_meta, &old_bytes, PlayerFormat::Encrypted) {
+        Ok(mem_plr) => mem_plr,
+        Err(err) => return Err(TestError::Load(err)),
+    };
+
+    match mem_plr.to_bytes(item_meta, mem_plr.format()) {
+        Ok(new_bytes) if new_bytes == old_bytes => {}
+        Ok(_) => return Err(TestError::Comparison),
+        Err(err) => return Err(TestError::Save(err)),
+    }
+
+    let json = match mem_plr.export_json(meta) {
+        Ok(json) => json,
+        Err(err) => return Err(TestError::Interchange(err)),
+    };
+
+    let document: serde_json::Value =
+        serde_json::from_str(&json).expect("Could not parse exported JSON");
+    if let Some(err) = schema.iter_errors(&document).next() {
+        return Err(TestError::Interchange(anyhow::anyhow!(
+            "{err} at `{}`",
+            err.instance_path
+        )));
+    }
+
+    match Player::import_json(&json, meta).and_then(|p| p.to_bytes(item_meta, mem_plr.format())) {
+        Ok(new_bytes) if new_bytes == old_bytes => {}
+        Ok(_) => return Err(TestError::Comparison),
+        Err(err) => return Err(TestError::Interchange(err)),
+    }
+
     Ok(())
 }
 
@@ -96,21 +136,30 @@ fn main() {
         serde_json::from_str(include_str!("../../../data/resources/prefixes.json"))
             .expect("Could not load prefixes");
 
-    println!("Items count: {}", item_meta.len());
-    println!("Buffs count: {}", buff_meta.len());
-    println!("Prefixes count: {}", prefix_meta.len());
+    let meta = MetaBundle::new(item_meta, buff_meta, prefix_meta);
+
+    println!("Items count: {}", meta.items.len());
+    println!("Buffs count: {}", meta.buffs.len());
+    println!("Prefixes count: {}", meta.prefixes.len());
+
+    let schema: serde_json::Value =
+        serde_json::from_str(PLAYER_SCHEMA).expect("Could not load player schema");
+    let schema = jsonschema::validator_for(&schema).expect("Player schema is invalid");
 
     let mut tests: Vec<String> = Vec::new();
     tests.extend(VERSIONS.iter().map(|v| format!("v{v}")));
     tests.push("テラリア".to_owned());
 
     for chara_name in tests {
-        match run_test(&chara_name, &player_dir, &item_meta) {
+        match run_test(&chara_name, &player_dir, &meta, &schema) {
             Ok(_) => println!("'{}.plr' loaded/saved successfully", &chara_name),
             Err(err) => match err {
                 TestError::Load(err) => println!("Error whilst loading\n---\n{:?}\n---", err),
                 TestError::Save(err) => println!("Error whilst saving\n---\n{:?}\n---", err),
                 TestError::Comparison => println!("Saved file was not the same as the loaded file"),
+                TestError::Interchange(err) => {
+                    println!("Error whilst exporting/importing JSON\n---\n{:?}\n---", err)
+                }
             },
         }
     }